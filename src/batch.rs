@@ -0,0 +1,85 @@
+//! batch.rs — converting many contours or glyphs to quads at once, in parallel
+//!
+//! Only built with the `rayon` feature. Contours are independent of one another, so they are
+//! shared out among rayon's threads; the results come back in the order of the input all the
+//! same, so a batch gives the same output however many threads convert it.
+
+use crate::co2qu::{ToQuads, Tolerance};
+use crate::cu2qu::ApproxNotFoundError;
+use crate::types::QuadSpline;
+
+use derive_more::{Add, AddAssign};
+use rayon::prelude::*;
+
+use std::iter::Sum;
+
+/// Counts of what a batch converted, summed over all of its contours.
+#[derive(Add, AddAssign, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchStats {
+    /// The number of contours converted, including those which failed.
+    pub contours: usize,
+    /// The number of segments (cubics, conics, …) in those contours.
+    pub segments: usize,
+    /// The number of quads in the contours which were converted.
+    pub quads: usize,
+    /// The number of contours with a segment which couldn't be approximated.
+    pub failures: usize,
+}
+
+impl Sum for BatchStats {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(BatchStats::default(), |sum, stats| sum + stats)
+    }
+}
+
+/// The result of converting a batch: one result per item of the input, in the same order.
+#[derive(Clone, Debug)]
+pub struct Batch<T> {
+    pub results: Vec<T>,
+    pub stats: BatchStats,
+}
+
+/// The result of converting one contour.
+pub type ContourResult = Result<QuadSpline, ApproxNotFoundError>;
+
+/// The trait for converting many contours (`[CubicSpline]`, `[Vec<Conic>]`) or glyphs (slices of
+/// `Vec`s of contours) to quads in parallel, as [`ToQuads`] does for each contour.
+///
+/// A contour which fails doesn't stop the others from being converted; it's counted in
+/// [`BatchStats::failures`].
+pub trait ParToQuads {
+    type Output;
+    fn par_to_quads(&self, tolerance: Tolerance) -> Batch<Self::Output>;
+}
+
+fn contour_stats<T>(contour: &[T], result: &ContourResult) -> BatchStats {
+    BatchStats {
+        contours: 1,
+        segments: contour.len(),
+        quads: result.as_ref().map_or(0, |quads| quads.len()),
+        failures: result.is_err() as usize,
+    }
+}
+
+impl<T: ToQuads + Sync> ParToQuads for [Vec<T>] {
+    type Output = ContourResult;
+
+    fn par_to_quads(&self, tolerance: Tolerance) -> Batch<ContourResult> {
+        let results: Vec<ContourResult> = self.par_iter().map(|contour| contour.to_quads(tolerance)).collect();
+        let stats = self.iter().zip(&results).map(|(contour, result)| contour_stats(contour, result)).sum();
+        Batch { results, stats }
+    }
+}
+
+/// Each glyph's contours are converted in parallel too, so one glyph with many contours doesn't
+/// keep a thread busy while the others idle.
+impl<T: ToQuads + Sync> ParToQuads for [Vec<Vec<T>>] {
+    type Output = Vec<ContourResult>;
+
+    fn par_to_quads(&self, tolerance: Tolerance) -> Batch<Vec<ContourResult>> {
+        let batches: Vec<Batch<ContourResult>> = self.par_iter().map(|glyph| glyph.par_to_quads(tolerance)).collect();
+        let stats = batches.iter().map(|batch| batch.stats).sum();
+        let results = batches.into_iter().map(|batch| batch.results).collect();
+        Batch { results, stats }
+    }
+}
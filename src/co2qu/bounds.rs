@@ -0,0 +1,51 @@
+use super::eval::EvalAt;
+use super::extrema::Extrema;
+use super::Conic;
+use crate::types::{Cubic, Quad, Rect};
+
+/// The trait for calculating the bounding box of a segment or spline.
+pub trait Bounds {
+    /// The bounding box of the control points. Cheap, but usually larger than the curve itself.
+    fn fast_bounds(&self) -> Rect;
+    /// The smallest bounding box containing the curve, found by evaluating its extrema.
+    fn tight_bounds(&self) -> Rect;
+}
+
+impl Bounds for Cubic {
+    fn fast_bounds(&self) -> Rect {
+        Rect::from_points(*self)
+    }
+    fn tight_bounds(&self) -> Rect {
+        Rect::from_points([self[0], self[3]].into_iter().chain(self.extrema().into_iter().map(|t| self.eval_at(t))))
+    }
+}
+
+impl Bounds for Quad {
+    fn fast_bounds(&self) -> Rect {
+        Rect::from_points(*self)
+    }
+    fn tight_bounds(&self) -> Rect {
+        Rect::from_points([self[0], self[2]].into_iter().chain(self.extrema().into_iter().map(|t| self.eval_at(t))))
+    }
+}
+
+impl Bounds for Conic {
+    fn fast_bounds(&self) -> Rect {
+        Rect::from_points([self.start, self.control, self.end])
+    }
+    // translation of SkConic::computeTightBounds
+    fn tight_bounds(&self) -> Rect {
+        Rect::from_points([self.start, self.end].into_iter().chain(self.extrema().into_iter().map(|t| self.eval_at(t))))
+    }
+}
+
+/// Splines (`CubicSpline`, `QuadSpline`, `Vec<Conic>`) are bounded by the union of their segments.
+/// An empty spline has the default (zero-sized) bounding box.
+impl<T: Bounds> Bounds for [T] {
+    fn fast_bounds(&self) -> Rect {
+        self.iter().map(Bounds::fast_bounds).reduce(|a, b| a.union(&b)).unwrap_or_default()
+    }
+    fn tight_bounds(&self) -> Rect {
+        self.iter().map(Bounds::tight_bounds).reduce(|a, b| a.union(&b)).unwrap_or_default()
+    }
+}
@@ -0,0 +1,129 @@
+use super::consts::MAX_QUAD_POW2;
+use super::deviation::max_distance;
+use super::Conic;
+use crate::cu2qu::{ApproxNotFoundError, CubicApprox, MAX_N};
+use crate::types::{Cubic, QuadSpline, Scalar};
+
+/// The trait for converting curves to quads when there is a budget for the number of quads (e.g.
+/// for the points of a `glyf` table entry) rather than a tolerance.
+///
+/// Splines (`CubicSpline`, `Vec<Conic>`) share the budget among their segments, giving each
+/// further quad to the segment farthest from its approximation, so that the contour as a whole is
+/// as near as it can be. Quads which wouldn't bring it nearer are left out of the budget.
+pub trait QuadBudget {
+    /// The spline of no more than `max_quads` quads nearest the curve, and the greatest distance
+    /// between them, as [`max_distance`] measures it.
+    ///
+    /// Fails if the spline is empty, the budget is smaller than the number of segments, or a cubic
+    /// can't be approximated within it.
+    fn quads_within_budget(&self, max_quads: usize) -> Result<(QuadSpline, Scalar), ApproxNotFoundError>;
+}
+
+/// A segment which can be approximated by a given number of quads.
+trait Budgeted {
+    /// The number of quads to try after `count`, if there is one.
+    fn next_count(&self, count: usize) -> Option<usize>;
+    /// An approximation by `count` quads (or fewer, where they would be lines), and its distance
+    /// from the segment.
+    fn quads_with(&self, count: usize) -> Option<(QuadSpline, Scalar)>;
+}
+
+impl Budgeted for Cubic {
+    /// No more than cu2qu would ever use, [`MAX_N`], so that a huge budget ends the search too.
+    fn next_count(&self, count: usize) -> Option<usize> {
+        (count < MAX_N).then_some(count + 1)
+    }
+
+    /// cu2qu's spline of `count` quads, whatever its error.
+    fn quads_with(&self, count: usize) -> Option<(QuadSpline, Scalar)> {
+        let quads = self.approx_spline(count, Scalar::INFINITY).ok()?;
+        let distance = max_distance(self, &quads).distance;
+        distance.is_finite().then_some((quads, distance))
+    }
+}
+
+impl Budgeted for Conic {
+    /// Conics are halved evenly, like [`quad_pow2`](super::QuadPowerOf2::quad_pow2) does.
+    fn next_count(&self, count: usize) -> Option<usize> {
+        (count < 1 << MAX_QUAD_POW2).then_some(count * 2)
+    }
+
+    fn quads_with(&self, count: usize) -> Option<(QuadSpline, Scalar)> {
+        let mut pow2 = count.trailing_zeros() as usize;
+        let quads = self.chop_into_quads_pow2(&mut pow2);
+        let distance = max_distance(self, &quads).distance;
+        Some((quads, distance))
+    }
+}
+
+/// The state of one segment of a spline while the budget is shared out.
+struct Share {
+    /// The greatest number of quads tried so far.
+    tried: usize,
+    /// The nearest approximation found so far.
+    best: Option<(QuadSpline, Scalar)>,
+}
+
+impl Share {
+    fn len(&self) -> usize {
+        self.best.as_ref().map_or(self.tried, |(quads, _)| quads.len())
+    }
+
+    fn distance(&self) -> Scalar {
+        self.best.as_ref().map_or(Scalar::INFINITY, |&(_, distance)| distance)
+    }
+}
+
+impl<T: Budgeted> QuadBudget for [T] {
+    fn quads_within_budget(&self, max_quads: usize) -> Result<(QuadSpline, Scalar), ApproxNotFoundError> {
+        if self.is_empty() || max_quads < self.len() {
+            return Err(ApproxNotFoundError);
+        }
+        let mut shares: Vec<Share> = self
+            .iter()
+            .map(|segment| Share {
+                tried: 1,
+                best: segment.quads_with(1),
+            })
+            .collect();
+        loop {
+            let used: usize = shares.iter().map(Share::len).sum();
+            // Only more quads for the farthest segment can bring the spline nearer.
+            let (i, share) = shares
+                .iter_mut()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.distance().total_cmp(&b.distance()))
+                .expect("The spline has segments");
+            let next = match self[i].next_count(share.tried) {
+                Some(next) if share.distance() > 0.0 && used - share.len() + next <= max_quads => next,
+                _ => break,
+            };
+            share.tried = next;
+            if let Some(found) = self[i].quads_with(next) {
+                if found.1 < share.distance() {
+                    share.best = Some(found);
+                }
+            }
+        }
+        let mut spline = QuadSpline::new();
+        let mut distance: Scalar = 0.0;
+        for share in shares {
+            let (quads, d) = share.best.ok_or(ApproxNotFoundError)?;
+            spline.extend(quads);
+            distance = distance.max(d);
+        }
+        Ok((spline, distance))
+    }
+}
+
+impl QuadBudget for Cubic {
+    fn quads_within_budget(&self, max_quads: usize) -> Result<(QuadSpline, Scalar), ApproxNotFoundError> {
+        std::slice::from_ref(self).quads_within_budget(max_quads)
+    }
+}
+
+impl QuadBudget for Conic {
+    fn quads_within_budget(&self, max_quads: usize) -> Result<(QuadSpline, Scalar), ApproxNotFoundError> {
+        std::slice::from_ref(self).quads_within_budget(max_quads)
+    }
+}
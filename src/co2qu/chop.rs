@@ -1,10 +1,9 @@
+use super::extrema::Extrema;
 use super::*;
-use crate::types::DerivativeCoefficients;
+use crate::coeffs::{Coefficient, ConicCoeff, FromWeightedSegment};
+use crate::types::{Cubic, Quad, Scalar};
 
-use std::f32::EPSILON;
-pub const EPSILON_F32: f32 = EPSILON * 10.;
-
-use num_traits::Float;
+pub const EPSILON_T: Scalar = Scalar::EPSILON * 10.;
 
 /// The trait allowing the curve to be subdivided into smaller pieces.
 pub trait Chop
@@ -12,11 +11,20 @@ where
     Self: Clone + Default + Sized,
 {
     /// divide curve into two at t
-    fn chop_at(&self, t: f32, dst: &mut [Self; 2]) -> bool;
+    fn chop_at(&self, t: Scalar, dst: &mut [Self; 2]) -> bool;
     /// get a subsection of the curve from t1…t2
-    fn chop_at_t2(&self, t1: f32, t2: f32) -> Self;
+    fn chop_at_t2(&self, t1: Scalar, t2: Scalar) -> Self;
     /// splits the curve into `2^level+1` linear pieces.
-    fn subdivide(&self, level: usize) -> Vec<Self>;
+    fn subdivide(&self, level: usize) -> Vec<Self> {
+        if level == 0 {
+            return vec![self.clone()];
+        }
+        let dst = self.chop();
+        let mut ret = Vec::new();
+        ret.extend(dst[0].subdivide(level - 1));
+        ret.extend(dst[1].subdivide(level - 1));
+        ret
+    }
     /// chop into 2 conics, with the first conic be the portion from t=0.0 to t=0.5, and the second going from t=0.5 to t=1.0
     fn chop(&self) -> [Self; 2] {
         [self.chop_at_t2(0.0, 0.5), self.chop_at_t2(0.5, 1.0)]
@@ -24,7 +32,7 @@ where
 }
 
 impl Chop for Conic {
-    fn chop_at(&self, t: f32, dst: &mut [Conic; 2]) -> bool {
+    fn chop_at(&self, t: Scalar, dst: &mut [Conic; 2]) -> bool {
         let src = [self.start.clone(), self.control.clone(), self.end.clone()];
         let tmp = p3d::ratquad_map(&src, self.weight);
         let tmp2 = p3d::interp(&tmp, t.into());
@@ -34,58 +42,257 @@ impl Chop for Conic {
         dst[1].start = dst[0].end.clone();
         dst[1].control = tmp2[2].into();
         dst[1].end = self.end.clone();
-        let root = Float::sqrt(tmp2[1].z());
-        dst[0].weight = (tmp2[0].z() / root).into();
-        dst[1].weight = (tmp2[1].z() / root).into();
-        return dst.into_iter().all(|c| {
-            [&c.start, &c.control, &c.end]
-                .into_iter()
-                .all(|p3| f32::from(p3.x()).is_finite() && f32::from(p3.y()).is_finite())
-        });
-    }
-
-    fn chop_at_t2(&self, t1: f32, t2: f32) -> Self {
-        let mut dst: Conic = Default::default();
-        if t1 < EPSILON_F32 || t2 > (1.0f32 - EPSILON_F32) {
-            if t1 < EPSILON_F32 && t2 > (1.0f32 - EPSILON_F32) {
-                dst = self.clone();
-                return dst;
+        let root = tmp2[1].z.sqrt();
+        dst[0].weight = tmp2[0].z / root;
+        dst[1].weight = tmp2[2].z / root;
+        return dst
+            .into_iter()
+            .all(|c| [&c.start, &c.control, &c.end].into_iter().all(|p| p.iter().all(|c| c.is_finite())));
+    }
+
+    // translation of SkConic::chopAt(SkScalar t1, SkScalar t2, SkConic* dst)
+    fn chop_at_t2(&self, t1: Scalar, t2: Scalar) -> Self {
+        if t1 < EPSILON_T || t2 > (1.0 - EPSILON_T) {
+            if t1 < EPSILON_T && t2 > (1.0 - EPSILON_T) {
+                return self.clone();
             } else {
                 let mut pair = [Conic::default(), Conic::default()];
-                if self.chop_at(if t1 > EPSILON_F32 { t1 } else { t2 }, &mut pair) {
-                    return pair[if t1 >= EPSILON_F32 { 0 } else { 1 }].clone();
+                if self.chop_at(if t1 >= EPSILON_T { t1 } else { t2 }, &mut pair) {
+                    return pair[if t1 >= EPSILON_T { 1 } else { 0 }].clone();
                 }
             }
         }
-        let coeff = self.deriv_coeff();
-        let coeff = (coeff[0], coeff[1], coeff[2]);
-        let tt1 = (t1 as f32, t1 as f32);
-        let a_xy = (coeff.0 * tt1.0 + coeff.1) * tt1.0 + coeff.2;
-        let a_zz = (coeff.0 + coeff.0) * tt1.0 + 1f32;
-        let mid_tt = ((t1 * 2f32 + t2) * 0.5f32, (t1 * 2f32 + t2) * 0.5f32);
-        let d_xy = (coeff.0 * mid_tt.0 + coeff.1) * mid_tt.0 + coeff.2;
-        let d_zz = (coeff.0 + coeff.0) * mid_tt.0 + 1f32;
-        let tt2 = (t2 as f32, t2 as f32);
-        let c_xy = (coeff.0 * tt2.0 + coeff.1) * tt2.0 + coeff.2;
-        let c_zz = (coeff.0 + coeff.0) * tt2.0 + 1f32;
-        let b_xy = d_xy * 2f32 - (a_xy + c_xy) * 0.5f32;
-        let b_zz = d_zz * 2f32 - (a_zz + c_zz) * 0.5f32;
-        dst.start = point![a_xy / a_zz, a_xy / a_zz];
-        dst.control = point![b_xy / b_zz, b_xy / b_zz];
-        dst.end = point![c_xy / c_zz, c_xy / c_zz];
-        let ww = b_zz / (a_zz * c_zz).sqrt();
-        dst.weight = ww;
-        dst
+        let coeff = ConicCoeff::from_segment([self.start, self.control, self.end], [self.weight]);
+        let tt1 = point![t1, t1];
+        let a_xy = coeff.numer.eval(tt1);
+        let a_zz = coeff.denom.eval(tt1);
+        let mid_tt = point![(t1 + t2) * 0.5, (t1 + t2) * 0.5];
+        let d_xy = coeff.numer.eval(mid_tt);
+        let d_zz = coeff.denom.eval(mid_tt);
+        let tt2 = point![t2, t2];
+        let c_xy = coeff.numer.eval(tt2);
+        let c_zz = coeff.denom.eval(tt2);
+        let b_xy = d_xy * 2.0 - (a_xy + c_xy) * 0.5;
+        let b_zz = d_zz * 2.0 - (a_zz + c_zz) * 0.5;
+        Conic {
+            start: Point(a_xy.component_div(&a_zz)),
+            control: Point(b_xy.component_div(&b_zz)),
+            end: Point(c_xy.component_div(&c_zz)),
+            weight: b_zz[0] / (a_zz[0] * c_zz[0]).sqrt(),
+        }
     }
+}
 
-    fn subdivide(&self, level: usize) -> Vec<Self> {
-        if level == 0 {
-            return vec![self.clone()];
+// translation of SkChopCubicAt
+impl Chop for Cubic {
+    fn chop_at(&self, t: Scalar, dst: &mut [Cubic; 2]) -> bool {
+        let [p0, p1, p2, p3] = *self;
+        let ab = Point(p0.lerp(&p1, t));
+        let bc = Point(p1.lerp(&p2, t));
+        let cd = Point(p2.lerp(&p3, t));
+        let abc = Point(ab.lerp(&bc, t));
+        let bcd = Point(bc.lerp(&cd, t));
+        let abcd = Point(abc.lerp(&bcd, t));
+        dst[0] = [p0, ab, abc, abcd];
+        dst[1] = [abcd, bcd, cd, p3];
+        dst.iter().flatten().all(|p| p.iter().all(|c| c.is_finite()))
+    }
+
+    fn chop_at_t2(&self, t1: Scalar, t2: Scalar) -> Self {
+        chop_polynomial_at_t2(self, t1, t2)
+    }
+}
+
+// translation of SkChopQuadAt
+impl Chop for Quad {
+    fn chop_at(&self, t: Scalar, dst: &mut [Quad; 2]) -> bool {
+        let [p0, p1, p2] = *self;
+        let ab = Point(p0.lerp(&p1, t));
+        let bc = Point(p1.lerp(&p2, t));
+        let abc = Point(ab.lerp(&bc, t));
+        dst[0] = [p0, ab, abc];
+        dst[1] = [abc, bc, p2];
+        dst.iter().flatten().all(|p| p.iter().all(|c| c.is_finite()))
+    }
+
+    fn chop_at_t2(&self, t1: Scalar, t2: Scalar) -> Self {
+        chop_polynomial_at_t2(self, t1, t2)
+    }
+}
+
+/// Polynomial segments can be chopped twice, as the parameter of the remaining piece scales
+/// linearly. (This is not so for conics, as the weight of the remainder changes.) Only the ends
+/// themselves are taken to be the ends, so however near them `t1` and `t2` are, the piece is the
+/// one asked for.
+fn chop_polynomial_at_t2<S: Chop>(segment: &S, t1: Scalar, t2: Scalar) -> S {
+    let mut pair = [S::default(), S::default()];
+    if t1 <= 0.0 && t2 >= 1.0 {
+        segment.clone()
+    } else if t1 <= 0.0 {
+        segment.chop_at(t2, &mut pair);
+        pair[0].clone()
+    } else {
+        segment.chop_at(t1, &mut pair);
+        if t2 >= 1.0 {
+            return pair[1].clone();
+        }
+        let rest = pair[1].clone();
+        rest.chop_at((t2 - t1) / (1.0 - t1), &mut pair);
+        pair[0].clone()
+    }
+}
+
+/// The trait for reading the end points of a segment, whatever its kind.
+pub trait Endpoints {
+    /// The start point.
+    fn start(&self) -> Point;
+    /// The end point.
+    fn end(&self) -> Point;
+}
+
+/// Access to the endpoints of a segment and the control points adjacent to them, used to flatten
+/// the segment at an extremum or to join segments.
+pub(crate) trait Hull: Endpoints {
+    /// The start point and the control point following it.
+    fn start_mut(&mut self) -> (&mut Point, &mut Point);
+    /// The end point and the control point preceding it.
+    fn end_mut(&mut self) -> (&mut Point, &mut Point);
+}
+
+impl Endpoints for Cubic {
+    fn start(&self) -> Point {
+        self[0]
+    }
+    fn end(&self) -> Point {
+        self[3]
+    }
+}
+
+impl Hull for Cubic {
+    fn start_mut(&mut self) -> (&mut Point, &mut Point) {
+        let [p0, p1, _, _] = self;
+        (p0, p1)
+    }
+    fn end_mut(&mut self) -> (&mut Point, &mut Point) {
+        let [_, _, p2, p3] = self;
+        (p3, p2)
+    }
+}
+
+impl Endpoints for Quad {
+    fn start(&self) -> Point {
+        self[0]
+    }
+    fn end(&self) -> Point {
+        self[2]
+    }
+}
+
+impl Hull for Quad {
+    fn start_mut(&mut self) -> (&mut Point, &mut Point) {
+        let [p0, p1, _] = self;
+        (p0, p1)
+    }
+    fn end_mut(&mut self) -> (&mut Point, &mut Point) {
+        let [_, p1, p2] = self;
+        (p2, p1)
+    }
+}
+
+impl Endpoints for Conic {
+    fn start(&self) -> Point {
+        self.start
+    }
+    fn end(&self) -> Point {
+        self.end
+    }
+}
+
+impl Hull for Conic {
+    fn start_mut(&mut self) -> (&mut Point, &mut Point) {
+        (&mut self.start, &mut self.control)
+    }
+    fn end_mut(&mut self) -> (&mut Point, &mut Point) {
+        (&mut self.end, &mut self.control)
+    }
+}
+
+/// The trait allowing the curve to be chopped into pieces which are monotonic in x and/or y, as
+/// needed by rasterizers, hinting and boolean operations.
+///
+/// Each returned piece starts exactly where the previous one ends, and the control points next to
+/// an extremum are flattened onto it, so that the pieces are monotonic despite rounding error.
+pub trait ChopAtExtrema: Chop + Extrema {
+    /// Chop at the extrema in x, giving pieces monotonic in x (like `SkChopCubicAtXExtrema`).
+    fn chop_at_x_extrema(&self) -> Vec<Self>;
+    /// Chop at the extrema in y, giving pieces monotonic in y (like `SkChopCubicAtYExtrema`).
+    fn chop_at_y_extrema(&self) -> Vec<Self>;
+    /// Chop at the extrema in both x and y, giving pieces monotonic in both axes.
+    fn make_monotonic(&self) -> Vec<Self>;
+}
+
+impl<S: Chop + Extrema + Hull> ChopAtExtrema for S {
+    fn chop_at_x_extrema(&self) -> Vec<Self> {
+        chop_at_extrema(self, self.x_extrema().into_iter().map(|t| (t, [true, false])).collect())
+    }
+
+    fn chop_at_y_extrema(&self) -> Vec<Self> {
+        chop_at_extrema(self, self.y_extrema().into_iter().map(|t| (t, [false, true])).collect())
+    }
+
+    fn make_monotonic(&self) -> Vec<Self> {
+        let xs = self.x_extrema().into_iter().map(|t| (t, [true, false]));
+        let ys = self.y_extrema().into_iter().map(|t| (t, [false, true]));
+        chop_at_extrema(self, xs.chain(ys).collect())
+    }
+}
+
+/// The trait allowing the curve to be chopped at several parameters at once, like Skia's
+/// `SkChopCubicAt(src, dst, tValues, count)`.
+pub trait ChopAtTValues: Chop {
+    /// Chop at each of the ascending `t_values`, returning `t_values.len() + 1` pieces. Each piece
+    /// starts exactly where the previous one ends.
+    fn chop_at_t_values(&self, t_values: &[Scalar]) -> Vec<Self>;
+}
+
+impl<S: Chop + Hull> ChopAtTValues for S {
+    fn chop_at_t_values(&self, t_values: &[Scalar]) -> Vec<Self> {
+        let mut pieces = Vec::with_capacity(t_values.len() + 1);
+        let mut t_prev = 0.0;
+        for &t in t_values {
+            pieces.push(self.chop_at_t2(t_prev, t));
+            t_prev = t;
+        }
+        pieces.push(self.chop_at_t2(t_prev, 1.0));
+        for i in 1..pieces.len() {
+            let (before, after) = pieces.split_at_mut(i);
+            *after[0].start_mut().0 = *before[i - 1].end_mut().0;
+        }
+        pieces
+    }
+}
+
+/// Chop `segment` at each _t_, flattening the axes marked `true` at each joint.
+fn chop_at_extrema<S: Chop + Hull>(segment: &S, mut t_values: Vec<(Scalar, [bool; 2])>) -> Vec<S> {
+    t_values.sort_by(|a, b| a.0.partial_cmp(&b.0).expect("Extrema are never NaN"));
+    // An extremum in both x and y at once (e.g. a cusp) is a single joint.
+    t_values.dedup_by(|b, a| {
+        if b.0 - a.0 < EPSILON_T {
+            a.1 = [a.1[0] || b.1[0], a.1[1] || b.1[1]];
+            true
+        } else {
+            false
+        }
+    });
+    let mut pieces = segment.chop_at_t_values(&t_values.iter().map(|&(t, _)| t).collect::<Vec<_>>());
+    for (i, &(_, axes)) in t_values.iter().enumerate() {
+        let (before, after) = pieces.split_at_mut(i + 1);
+        let (prev_end, prev_control) = before[i].end_mut();
+        let (_, next_control) = after[0].start_mut();
+        for axis in (0..2).filter(|&axis| axes[axis]) {
+            prev_control[axis] = prev_end[axis];
+            next_control[axis] = prev_end[axis];
         }
-        let dst = self.chop();
-        let mut ret = Vec::new();
-        ret.extend(dst[0].subdivide(level - 1));
-        ret.extend(dst[1].subdivide(level - 1));
-        ret
     }
+    pieces
 }
@@ -0,0 +1,155 @@
+use super::eval::{Curvature, EvalTangentAt};
+use super::{Conic, Hull};
+use crate::types::{Cubic, Point, Quad, Scalar};
+
+/// How smoothly two consecutive segments of a spline join.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Joint {
+    /// The joint is between segment `index` and the next one (segment 0, for the closing joint).
+    pub index: usize,
+    /// Distance between the end of the segment and the start of the next one. Zero if the spline
+    /// is G0 (positionally continuous) here.
+    pub gap: Scalar,
+    /// Angle in radians, from 0 to π, between the tangents on either side of the joint. Zero if
+    /// the spline is G1 (tangent continuous) here.
+    pub angle: Scalar,
+    /// Absolute difference of the signed curvatures on either side of the joint. Zero if the
+    /// spline is also G2 (curvature continuous) here.
+    pub curvature_jump: Scalar,
+}
+
+impl Joint {
+    /// Whether the segments meet within `gap_tolerance`, with tangents within `angle_tolerance`
+    /// radians of each other.
+    pub fn is_g1(&self, gap_tolerance: Scalar, angle_tolerance: Scalar) -> bool {
+        self.gap <= gap_tolerance && self.angle <= angle_tolerance
+    }
+
+    /// Whether the joint [`is_g1`](Self::is_g1), and the curvature jumps by no more than
+    /// `curvature_tolerance`.
+    pub fn is_g2(&self, gap_tolerance: Scalar, angle_tolerance: Scalar, curvature_tolerance: Scalar) -> bool {
+        self.is_g1(gap_tolerance, angle_tolerance) && self.curvature_jump <= curvature_tolerance
+    }
+}
+
+/// The trait for analyzing the continuity of splines (`CubicSpline`, `QuadSpline`, `Vec<Conic>`)
+/// at the joints between their segments.
+pub trait Continuity {
+    /// Report on each joint of the spline, in order. If `closed`, the joint between the last and
+    /// the first segment is included last.
+    fn joints(&self, closed: bool) -> Vec<Joint>;
+}
+
+impl<T: Curvature + EvalTangentAt + Hull> Continuity for [T] {
+    fn joints(&self, closed: bool) -> Vec<Joint> {
+        let count = match (self.len(), closed) {
+            (0, _) => 0,
+            (len, true) => len,
+            (len, false) => len - 1,
+        };
+        (0..count)
+            .map(|index| {
+                let (before, after) = (&self[index], &self[(index + 1) % self.len()]);
+                let (incoming, outgoing) = (before.eval_tangent_at(1.0), after.eval_tangent_at(0.0));
+                Joint {
+                    index,
+                    gap: (after.start() - before.end()).norm(),
+                    angle: incoming.perp(&outgoing).atan2(incoming.dot(&outgoing)).abs(),
+                    curvature_jump: (after.curvature_at(0.0) - before.curvature_at(1.0)).abs(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns segment `index` and the one after it (wrapping around), mutably, if the spline has such
+/// a joint.
+fn joint_mut<T>(spline: &mut [T], index: usize) -> Option<(&mut T, &mut T)> {
+    if index >= spline.len() {
+        None
+    } else if index + 1 == spline.len() {
+        let (first, rest) = spline.split_at_mut(1);
+        Some((rest.last_mut().expect("The spline has two segments"), &mut first[0]))
+    } else {
+        let (left, right) = spline.split_at_mut(index + 1);
+        Some((&mut left[index], &mut right[0]))
+    }
+}
+
+/// Moves the on-curve point of a joint to the nearest point between its neighbouring control
+/// points, which is the only way to make a quad or conic joint smooth without disturbing the other
+/// end of either segment.
+fn smooth_by_moving_joint<T: Hull>(spline: &mut [T], joints: &[usize]) {
+    if spline.len() < 2 {
+        return;
+    }
+    for &index in joints {
+        let (before, after) = match joint_mut(spline, index) {
+            Some(joint) => joint,
+            None => continue,
+        };
+        let (end, c_in) = before.end_mut();
+        let (start, c_out) = after.start_mut();
+        let line = *c_out - *c_in;
+        if line.norm_squared() > 0.0 {
+            let u = ((*end - *c_in).dot(&line) / line.norm_squared()).clamp(0.0, 1.0);
+            *end = *c_in + line * u;
+        }
+        *start = *end;
+    }
+}
+
+/// The trait for making chosen joints of a spline exactly G1, for instance smooth points that
+/// drifted off collinear through rounding error.
+///
+/// Joints are numbered as in [`Continuity::joints`]: joint `i` is between segment `i` and the next
+/// one, and joint `len - 1` closes the spline. Unlisted joints are left alone, as are splines of
+/// fewer than two segments; listed joints past the closing one are ignored.
+pub trait Smooth {
+    /// Make each of the `joints` G0 and G1, changing the shape as little as possible.
+    fn smooth_joints(&mut self, joints: &[usize]);
+}
+
+/// The on-curve point stays put and each handle keeps its length; both are rotated onto the line
+/// that moves them least (in the least squares sense). A zero-length handle is left alone.
+///
+/// Equal handles, as at a cusp, are as far from one line as from any other, so they are rotated
+/// onto the chord between the on-curve points either side of the joint, or onto themselves if that
+/// has no length.
+impl Smooth for [Cubic] {
+    fn smooth_joints(&mut self, joints: &[usize]) {
+        if self.len() < 2 {
+            return;
+        }
+        for &index in joints {
+            let (before, after) = match joint_mut(self, index) {
+                Some(joint) => joint,
+                None => continue,
+            };
+            let anchor = before[3];
+            after[0] = anchor;
+            let (h_in, h_out) = (before[2] - anchor, after[1] - anchor);
+            let candidates = [h_out * h_out.norm() - h_in * h_in.norm(), after[3] - before[0], h_out];
+            let direction = match candidates.into_iter().find(|direction| direction.norm_squared() > 0.0) {
+                Some(direction) => Point(direction.normalize()),
+                None => continue,
+            };
+            before[2] = anchor - direction * h_in.norm();
+            after[1] = anchor + direction * h_out.norm();
+        }
+    }
+}
+
+/// The on-curve point moves onto the line between its neighbouring off-curve points.
+impl Smooth for [Quad] {
+    fn smooth_joints(&mut self, joints: &[usize]) {
+        smooth_by_moving_joint(self, joints)
+    }
+}
+
+/// The on-curve point moves onto the line between its neighbouring control points.
+impl Smooth for [Conic] {
+    fn smooth_joints(&mut self, joints: &[usize]) {
+        smooth_by_moving_joint(self, joints)
+    }
+}
@@ -0,0 +1,82 @@
+use super::length::{ArcLength, LengthWalker};
+use super::{Chop, DerivativeAt, Hull};
+use crate::Scalar;
+
+/// The trait for dashing splines (`CubicSpline`, `QuadSpline`, `Vec<Conic>`), like Skia's
+/// `SkDashPathEffect`.
+///
+/// The dashes are sub-splines of the original, cut out of its segments by exact chopping. A dash
+/// carries on across segment joints, so it may be made of pieces of several segments.
+pub trait Dash {
+    type Segment;
+    /// Dash the spline with `intervals` of alternating “on” and “off” lengths, starting `phase`
+    /// units into the pattern (which may be negative). Zero-length “on” intervals give
+    /// zero-length dashes, which are drawn by round or square caps.
+    ///
+    /// Panics unless there is an even number of `intervals`, none negative and not all zero.
+    fn dash(&self, intervals: &[Scalar], phase: Scalar, tolerance: Scalar) -> Vec<Vec<Self::Segment>>;
+}
+
+/// The part of the spline between the (index, _t_) positions `from` and `to`.
+fn sub_spline<T: Chop + Hull>(spline: &[T], from: (usize, Scalar), to: (usize, Scalar)) -> Vec<T> {
+    // A dash ending on a joint ends at the end of the segment before it.
+    let to = if to.0 > from.0 && to.1 == 0.0 { (to.0 - 1, 1.0) } else { to };
+    let mut pieces: Vec<T> = (from.0..=to.0)
+        .map(|index| {
+            let t1 = if index == from.0 { from.1 } else { 0.0 };
+            let t2 = if index == to.0 { to.1 } else { 1.0 };
+            let segment = &spline[index];
+            if t1 == 0.0 && t2 == 1.0 {
+                return segment.clone();
+            }
+            let mut piece = segment.chop_at_t2(t1, t2);
+            // Keep the segment's own end points, rather than ones found by rounding.
+            if t1 == 0.0 {
+                *piece.start_mut().0 = segment.start();
+            }
+            if t2 == 1.0 {
+                *piece.end_mut().0 = segment.end();
+            }
+            piece
+        })
+        .collect();
+    for i in 1..pieces.len() {
+        let (before, after) = pieces.split_at_mut(i);
+        *after[0].start_mut().0 = before[i - 1].end();
+    }
+    pieces
+}
+
+impl<T: ArcLength + Chop + DerivativeAt + Hull> Dash for [T] {
+    type Segment = T;
+
+    fn dash(&self, intervals: &[Scalar], phase: Scalar, tolerance: Scalar) -> Vec<Vec<T>> {
+        assert!(intervals.len() >= 2 && intervals.len().is_multiple_of(2), "Dash intervals come in on/off pairs");
+        assert!(intervals.iter().all(|&i| i >= 0.0), "Dash intervals may not be negative");
+        let pattern: Scalar = intervals.iter().sum();
+        assert!(pattern > 0.0, "Dash intervals may not all be zero");
+        if self.is_empty() {
+            return vec![];
+        }
+        let mut walker = LengthWalker::new(self, tolerance);
+        let total = walker.total();
+        // Start the pattern before the spline, so that the spline starts `phase` into it.
+        let mut position = -phase.rem_euclid(pattern);
+        let mut dashes = vec![];
+        for (i, &interval) in intervals.iter().enumerate().cycle() {
+            // A dash starting within the tolerance of the end may be nothing but rounding error.
+            if position >= total - tolerance {
+                break;
+            }
+            let (start, end) = (position, position + interval);
+            position = end;
+            if i % 2 == 1 || (start < 0.0 && end <= 0.0) {
+                continue;
+            }
+            let from = walker.locate(start.max(0.0));
+            let to = if end >= total { (self.len() - 1, 1.0) } else { walker.locate(end) };
+            dashes.push(sub_spline(self, from, to));
+        }
+        dashes
+    }
+}
@@ -0,0 +1,100 @@
+use super::eval::{DerivativeAt, EvalAt};
+use super::length::integrate;
+use super::nearest::Nearest;
+use crate::Scalar;
+
+/// The greatest distance between a segment and a spline approximating it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Deviation {
+    /// The Hausdorff distance: how far the farthest point of either curve is from the other curve.
+    pub distance: Scalar,
+    /// The parameter on the original segment at (or, if the farthest point is on the
+    /// approximation, nearest to) the greatest distance.
+    pub t: Scalar,
+}
+
+// Number of intervals per segment in which to look for the greatest distance.
+const SAMPLES: usize = 64;
+
+/// Finds the greatest `distance(t)` for _t_ in 0…1, by sampling it and then refining every sample
+/// greater than its neighbours by golden-section search, as the greatest sample need not be on the
+/// highest peak. Returns (_t_, distance).
+fn farthest(distance: impl Fn(Scalar) -> Scalar) -> (Scalar, Scalar) {
+    const INV_PHI: Scalar = 0.618_034;
+    let step = 1.0 / SAMPLES as Scalar;
+    let samples: Vec<(Scalar, Scalar)> = (0..=SAMPLES).map(|i| (i as Scalar * step, distance(i as Scalar * step))).collect();
+    let mut best = (0.0, Scalar::NEG_INFINITY);
+    for (i, &sample) in samples.iter().enumerate() {
+        let before = if i == 0 { Scalar::NEG_INFINITY } else { samples[i - 1].1 };
+        let after = if i == SAMPLES { Scalar::NEG_INFINITY } else { samples[i + 1].1 };
+        if sample.1 < before || sample.1 < after {
+            continue;
+        }
+        let (mut lo, mut hi) = (i.saturating_sub(1) as Scalar * step, (i + 1).min(SAMPLES) as Scalar * step);
+        while hi - lo > Scalar::EPSILON * 4.0 {
+            let (m1, m2) = (hi - (hi - lo) * INV_PHI, lo + (hi - lo) * INV_PHI);
+            if distance(m1) > distance(m2) {
+                hi = m2;
+            } else {
+                lo = m1;
+            }
+        }
+        // The search only approaches the sample if it is at an end.
+        let refined = ((lo + hi) / 2.0, distance((lo + hi) / 2.0));
+        for candidate in [sample, refined] {
+            if candidate.1 > best.1 {
+                best = candidate;
+            }
+        }
+    }
+    best
+}
+
+/// Measure the greatest distance between `original` and the spline `approximation`, in both
+/// directions: from every point of the original to the approximation, and from every point of the
+/// approximation to the original.
+///
+/// This works for any converter's output, e.g. a cubic and the quads of cu2qu, a conic and those of
+/// [`Conic::as_quads`](super::Conic::as_quads), or a quad and the cubic of qu2cu.
+///
+/// Panics if `approximation` is empty.
+pub fn max_distance<S: EvalAt + Nearest, A: EvalAt + Nearest>(original: &S, approximation: &[A]) -> Deviation {
+    let (t, distance) = farthest(|t| approximation.nearest(original.eval_at(t)).distance);
+    let mut deviation = Deviation { distance, t };
+    for segment in approximation {
+        let (t, distance) = farthest(|t| original.nearest(segment.eval_at(t)).distance);
+        if distance > deviation.distance {
+            deviation = Deviation {
+                distance,
+                t: original.nearest(segment.eval_at(t)).t,
+            };
+        }
+    }
+    deviation
+}
+
+/// ∫ _P_ × _P′_ / 2 d_t_, the signed area swept by the line from the origin to the point as it
+/// moves along the segment.
+fn swept_area<S: DerivativeAt + EvalAt>(segment: &S, tolerance: Scalar) -> Scalar {
+    integrate(&|t| segment.eval_at(t).perp(&segment.derivative_at(t)) / 2.0, 1.0, tolerance)
+}
+
+/// Measure the signed area between `original` and the spline `approximation`, within
+/// `tolerance`. It is positive where the original bulges to the right of the approximation (so
+/// outwards, on a counterclockwise contour with y pointing up) and negative where it bulges to the
+/// left.
+///
+/// Where the curves cross, areas on either side cancel out. The curves' end points are assumed to
+/// be joined, as they are for any converter's output.
+pub fn area_difference<S: DerivativeAt + EvalAt, A: DerivativeAt + EvalAt>(
+    original: &S,
+    approximation: &[A],
+    tolerance: Scalar,
+) -> Scalar {
+    let tolerance = tolerance / (approximation.len() + 1) as Scalar;
+    // The original and the reversed approximation make a closed loop, whose area is the
+    // difference of their swept areas.
+    approximation
+        .iter()
+        .fold(swept_area(original, tolerance), |area, segment| area - swept_area(segment, tolerance))
+}
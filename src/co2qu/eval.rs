@@ -1,49 +1,290 @@
+use super::extrema::find_unit_cubic_roots;
 use super::Conic;
-use crate::types::DerivativeCoefficients;
+use crate::coeffs::{Coefficient, ConicCoeff, CubicCoeff, FromSegment, FromWeightedSegment, QuadCoeff};
+use crate::types::{Cubic, DerivativeCoefficients, Quad, Scalar};
 use crate::{point, Point};
-use glifparser::PointLike;
 
 impl DerivativeCoefficients<3> for Conic {
     /// Compute the coefficients of the derivative of the conic.
-    fn deriv_coeff(&self) -> [f32; 3] {
-        let p0 = &self.start;
-        let p1 = &self.control;
-        let p2 = &self.end;
+    fn deriv_coeff(&self) -> [Point; 3] {
+        let p0 = self.start;
+        let p1 = self.control;
+        let p2 = self.end;
         let w = self.weight;
         let (a, b, c) = (
-            2f32 * p0.x() - 2f32 * p2.x() - 2f32 * p0.x() * w + 2f32 * p2.x() * w,
-            -2f32 * p0.x() + 2f32 * p2.x() + 4f32 * p0.x() * w - 4f32 * p1.x() * w,
-            -2f32 * p0.x() * w + 2f32 * p1.x() * w,
+            2.0 * p0 - 2.0 * p2 - 2.0 * p0 * w + 2.0 * p2 * w,
+            -2.0 * p0 + 2.0 * p2 + 4.0 * p0 * w - 4.0 * p1 * w,
+            -2.0 * p0 * w + 2.0 * p1 * w,
         );
-        return [a.into(), b.into(), c.into()];
+        [a, b, c]
     }
 }
 
-pub(crate) trait EvalTangentAt {
-    fn eval_tangent_at(&self, t: f32) -> Point;
+impl DerivativeCoefficients<3> for Cubic {
+    /// Compute the coefficients of the derivative of the cubic.
+    fn deriv_coeff(&self) -> [Point; 3] {
+        let [p0, p1, p2, p3] = *self;
+        [3.0 * (p3 - p0 + 3.0 * (p1 - p2)), 6.0 * (p0 - p1 * 2.0 + p2), 3.0 * (p1 - p0)]
+    }
+}
+
+impl DerivativeCoefficients<2> for Quad {
+    /// Compute the coefficients of the derivative of the quadratic.
+    fn deriv_coeff(&self) -> [Point; 2] {
+        let [p0, p1, p2] = *self;
+        [2.0 * (p0 - p1 * 2.0 + p2), 2.0 * (p1 - p0)]
+    }
+}
+
+/// Evaluate the point on a segment at _t_.
+pub trait EvalAt {
+    fn eval_at(&self, t: Scalar) -> Point;
+}
+
+impl EvalAt for Conic {
+    // translation of SkConic::evalAt
+    fn eval_at(&self, t: Scalar) -> Point {
+        ConicCoeff::from_segment([self.start, self.control, self.end], [self.weight]).eval(point![t, t])
+    }
+}
+
+impl EvalAt for Cubic {
+    fn eval_at(&self, t: Scalar) -> Point {
+        CubicCoeff::from_segment(*self).eval(point![t, t])
+    }
+}
+
+impl EvalAt for Quad {
+    fn eval_at(&self, t: Scalar) -> Point {
+        QuadCoeff::from_segment(*self).eval(point![t, t])
+    }
+}
+
+/// Evaluate the tangent of a segment at _t_.
+///
+/// The tangent points in the direction of travel, but is not normalized. For cubics and quads it
+/// is the derivative; for conics it is only proportional to it.
+pub trait EvalTangentAt {
+    fn eval_tangent_at(&self, t: Scalar) -> Point;
 }
 
 impl EvalTangentAt for Conic {
     // translation of SkConic::evalTangentAt
-    fn eval_tangent_at(&self, t: f32) -> Point {
+    fn eval_tangent_at(&self, t: Scalar) -> Point {
         // The derivative equation returns a zero tangent vector when t is 0 or 1,
         // and the control point is equal to the end point.
         // In this case, use the conic endpoints to compute the tangent.
         if (t == 0.0 && self.start == self.control) || (t == 1.0 && self.control == self.end) {
-            return point![(self.end.x() - self.start.x()).into(), (self.end.y() - self.start.y()).into()];
+            return self.end - self.start;
+        }
+        let p20 = self.end - self.start;
+        let p10 = self.control - self.start;
+        let c = self.weight * p10;
+        let a = self.weight * p20 - p20;
+        let b = p20 - c * 2.0;
+        (a * t + b) * t + c
+    }
+}
+
+impl EvalTangentAt for Cubic {
+    // translation of SkEvalCubicTangentAt
+    fn eval_tangent_at(&self, t: Scalar) -> Point {
+        let [p0, p1, p2, p3] = *self;
+        // The derivative equation returns a zero tangent vector when t is 0 or 1, and the
+        // adjacent control point is equal to the end point. In this case, use the next control
+        // point or the end points to compute the tangent.
+        if (t == 0.0 && p0 == p1) || (t == 1.0 && p2 == p3) {
+            let tangent = if t == 0.0 { p2 - p0 } else { p3 - p1 };
+            return if tangent == point![0.0, 0.0] { p3 - p0 } else { tangent };
+        }
+        let [a, b, c] = self.deriv_coeff();
+        (a * t + b) * t + c
+    }
+}
+
+impl EvalTangentAt for Quad {
+    // translation of SkEvalQuadTangentAt
+    fn eval_tangent_at(&self, t: Scalar) -> Point {
+        let [p0, p1, p2] = *self;
+        // The derivative equation is 2(b - a +(a - 2b +c)t). This returns a zero tangent vector
+        // when t is 0 or 1, the control point is equal to the end point. In this case, use the
+        // quad end points to compute the tangent.
+        if (t == 0.0 && p0 == p1) || (t == 1.0 && p1 == p2) {
+            return p2 - p0;
+        }
+        let [a, b] = self.deriv_coeff();
+        a * t + b
+    }
+}
+
+/// Evaluate the first derivative _P′_ of a segment at _t_, where [`EvalTangentAt`] may only give
+/// a vector proportional to it.
+pub trait DerivativeAt {
+    fn derivative_at(&self, t: Scalar) -> Point;
+}
+
+impl DerivativeAt for Cubic {
+    fn derivative_at(&self, t: Scalar) -> Point {
+        let [a, b, c] = self.deriv_coeff();
+        (a * t + b) * t + c
+    }
+}
+
+impl DerivativeAt for Quad {
+    fn derivative_at(&self, t: Scalar) -> Point {
+        let [a, b] = self.deriv_coeff();
+        a * t + b
+    }
+}
+
+impl DerivativeAt for Conic {
+    fn derivative_at(&self, t: Scalar) -> Point {
+        self.derivatives_at(t)[1]
+    }
+}
+
+/// Signed curvature _κ_ = (_P′_ × _P″_) / |_P′_|³ of the first and second derivatives.
+fn signed_curvature(d1: Point, d2: Point) -> Scalar {
+    d1.perp(&d2) / d1.norm().powi(3)
+}
+
+/// The trait for the curvature of a segment, as needed for curvature combs.
+///
+/// Curvature is positive where the curve turns counterclockwise (to the left, with y pointing up)
+/// and negative where it turns clockwise. It is the reciprocal of the radius of the osculating
+/// circle, and is not finite where the derivative vanishes, such as at a cusp.
+pub trait Curvature {
+    /// The signed curvature at _t_.
+    fn curvature_at(&self, t: Scalar) -> Scalar;
+    /// Parameters in 0…1 where the curvature is greatest, ascending.
+    fn max_curvature(&self) -> Vec<Scalar>;
+    /// The curvature at `samples + 1` evenly spaced parameters from 0 to 1, as `(t, κ)` pairs.
+    fn curvature_profile(&self, samples: usize) -> Vec<(Scalar, Scalar)> {
+        (0..=samples)
+            .map(|i| {
+                let t = i as Scalar / samples.max(1) as Scalar;
+                (t, self.curvature_at(t))
+            })
+            .collect()
+    }
+}
+
+impl Curvature for Quad {
+    fn curvature_at(&self, t: Scalar) -> Scalar {
+        let [a, b] = self.deriv_coeff();
+        signed_curvature(a * t + b, a)
+    }
+
+    // translation of SkFindQuadMaxCurvature
+    /// The single parameter where the derivative is perpendicular to the second derivative (the
+    /// vertex of the parabola), pinned to 0…1.
+    fn max_curvature(&self) -> Vec<Scalar> {
+        let [p0, p1, p2] = *self;
+        let a = p1 - p0;
+        let b = p0 - p1 * 2.0 + p2;
+        let mut numer = -a.dot(&b);
+        let mut denom = b.dot(&b);
+        if denom < 0.0 {
+            numer = -numer;
+            denom = -denom;
+        }
+        let t = if numer <= 0.0 {
+            0.0
+        } else if numer >= denom {
+            1.0
+        } else {
+            numer / denom
+        };
+        vec![t]
+    }
+}
+
+impl Curvature for Cubic {
+    fn curvature_at(&self, t: Scalar) -> Scalar {
+        let [a, b, c] = self.deriv_coeff();
+        signed_curvature((a * t + b) * t + c, 2.0 * a * t + b)
+    }
+
+    // translation of SkFindCubicMaxCurvature
+    /// Like Skia, the parameters where the derivative is perpendicular to the second derivative,
+    /// pinned to 0…1. These include every curvature maximum, but may also include minima.
+    fn max_curvature(&self) -> Vec<Scalar> {
+        let [p0, p1, p2, p3] = *self;
+        let a = p1 - p0;
+        let b = p2 - p1 * 2.0 + p0;
+        let c = p3 + 3.0 * (p1 - p2) - p0;
+        let mut t_values = [0.0; 3];
+        let roots = find_unit_cubic_roots(c.dot(&c), 3.0 * b.dot(&c), 2.0 * b.dot(&b) + c.dot(&a), a.dot(&b), &mut t_values);
+        t_values[..roots].to_vec()
+    }
+}
+
+impl Conic {
+    /// The point and its first and second derivatives at _t_.
+    pub(crate) fn derivatives_at(&self, t: Scalar) -> [Point; 3] {
+        // The conic is N(t)/D(t), with N(t) = At² + Bt + C and D(t) = -bt² + bt + 1.
+        let pw = self.control * self.weight;
+        let (a, b, c) = (self.end - pw * 2.0 + self.start, (pw - self.start) * 2.0, self.start);
+        let db = 2.0 * (self.weight - 1.0);
+        let (n, dn, ddn) = ((a * t + b) * t + c, 2.0 * a * t + b, 2.0 * a);
+        let (d, dd, ddd) = ((-db * t + db) * t + 1.0, -2.0 * db * t + db, -2.0 * db);
+        let p = n / d;
+        let dp = (dn - p * dd) / d;
+        // From N = PD: N″ = P″D + 2P′D′ + PD″.
+        let ddp = (ddn - dp * (2.0 * dd) - p * ddd) / d;
+        [p, dp, ddp]
+    }
+}
+
+// Number of intervals in which to look for curvature maxima of a conic.
+const CONIC_CURVATURE_SAMPLES: usize = 32;
+
+impl Curvature for Conic {
+    fn curvature_at(&self, t: Scalar) -> Scalar {
+        let [_, d1, d2] = self.derivatives_at(t);
+        signed_curvature(d1, d2)
+    }
+
+    /// The parameters of the local maxima of |_κ_|, including an end point if the curvature is
+    /// greatest there. A conic has at most two, at the vertices of its axes.
+    ///
+    /// Found numerically, by sampling the curvature and refining each maximum by golden-section
+    /// search. A circular arc is curved as much everywhere, so none of its parameters is given.
+    fn max_curvature(&self) -> Vec<Scalar> {
+        let abs_curvature = |t: Scalar| self.curvature_at(t).abs();
+        let step = 1.0 / CONIC_CURVATURE_SAMPLES as Scalar;
+        let samples: Vec<Scalar> = (0..=CONIC_CURVATURE_SAMPLES).map(|i| abs_curvature(i as Scalar * step)).collect();
+        let least = samples.iter().copied().fold(Scalar::INFINITY, Scalar::min);
+        let most = samples.iter().copied().fold(0.0, Scalar::max);
+        if most - least <= most * Scalar::EPSILON.sqrt() {
+            return vec![];
+        }
+        let mut t_values = vec![];
+        for i in 0..=CONIC_CURVATURE_SAMPLES {
+            let before = if i == 0 { Scalar::NEG_INFINITY } else { samples[i - 1] };
+            let after = if i == CONIC_CURVATURE_SAMPLES { Scalar::NEG_INFINITY } else { samples[i + 1] };
+            if samples[i] < before || samples[i] <= after {
+                continue;
+            }
+            let (mut lo, mut hi) = (i.saturating_sub(1) as Scalar * step, (i + 1).min(CONIC_CURVATURE_SAMPLES) as Scalar * step);
+            // golden-section search
+            const INV_PHI: Scalar = 0.618_034;
+            while hi - lo > Scalar::EPSILON * 4.0 {
+                let (m1, m2) = (hi - (hi - lo) * INV_PHI, lo + (hi - lo) * INV_PHI);
+                if abs_curvature(m1) < abs_curvature(m2) {
+                    lo = m1;
+                } else {
+                    hi = m2;
+                }
+            }
+            let t = (lo + hi) / 2.0;
+            // The curvature may be greatest at an end point, which the search only approaches.
+            let end = [0.0, 1.0]
+                .into_iter()
+                .find(|&end| (end - t).abs() <= step && abs_curvature(end) >= abs_curvature(t));
+            t_values.push(end.unwrap_or(t));
         }
-        let (s, c, e, k, w, a, _x, _y);
-        as_quad_error_setup!(&self, s, c, e, k, w, a, _x, _y);
-        let p0 = s.clone();
-        let p1 = c.clone();
-        let p2 = e.clone();
-        let p20 = point![(p2.x() - p0.x()).into(), (p2.y() - p0.y()).into()];
-        let p10 = point![(p1.x() - p0.x()).into(), (p1.y() - p0.y()).into()];
-        let c = p10 * w;
-        let a = w * p20 - p20;
-        let b = p20 - (c * 2f32);
-        let numer = ((a * t).component_mul(&b)) * t + c;
-        let denom = t + t + 1f32;
-        return numer / denom;
+        t_values.dedup_by(|b, a| *b - *a < Scalar::EPSILON * 10.0);
+        t_values
     }
 }
@@ -1,36 +1,156 @@
-// loosely based on Skia's SkFindUnitQuadRoots
-fn find_unit_quad_roots(a: f32, b: f32, c: f32, t_values: &mut [f32; 2]) -> usize {
+use super::Conic;
+use crate::types::{Cubic, DerivativeCoefficients, Quad, Scalar};
+
+use crate::scalar_consts::PI;
+
+// translation of Skia's valid_unit_divide
+/// Stores `numer / denom` in `ratio` and returns 1 if it lies strictly between 0 and 1, else 0.
+fn valid_unit_divide(mut numer: Scalar, mut denom: Scalar, ratio: &mut Scalar) -> usize {
+    if numer < 0.0 {
+        numer = -numer;
+        denom = -denom;
+    }
+    if denom == 0.0 || numer == 0.0 || numer >= denom {
+        return 0;
+    }
+    let r = numer / denom;
+    if r.is_nan() || r == 0.0 {
+        return 0;
+    }
+    *ratio = r;
+    1
+}
+
+// translation of Skia's SkFindUnitQuadRoots
+/// Finds the roots of _at² + bt + c_ strictly between 0 and 1, ascending and without duplicates.
+#[cfg_attr(feature = "f64", allow(clippy::unnecessary_cast))]
+pub(crate) fn find_unit_quad_roots(a: Scalar, b: Scalar, c: Scalar, t_values: &mut [Scalar; 2]) -> usize {
     if a == 0.0 {
-        t_values[0] = -c / b;
-        return (t_values[0] >= 0f32 && t_values[0] <= 1f32) as usize;
-    }
-    let dr = (b * b - 4f32 * a * c).sqrt();
-    let r = if b < 0.0 { -(b-dr)/2f32 } else { -(b+dr)/2f32 };
-    let q = c / r;
-    let mut t_count = 0;
-    if r != 0.0 {
-        let t = r / a;
-        if t >= 0.0 && t <= 1.0 {
-            t_values[t_count] = t;
-            t_count += 1;
+        return valid_unit_divide(-c, b, &mut t_values[0]);
+    }
+    let dr = (b as f64) * (b as f64) - 4f64 * (a as f64) * (c as f64);
+    if dr < 0.0 {
+        return 0;
+    }
+    let r = dr.sqrt() as Scalar;
+    if !r.is_finite() {
+        return 0;
+    }
+    let q = if b < 0.0 { -(b - r) / 2.0 } else { -(b + r) / 2.0 };
+    let mut t_count = valid_unit_divide(q, a, &mut t_values[0]);
+    t_count += valid_unit_divide(c, q, &mut t_values[t_count]);
+    if t_count == 2 {
+        if t_values[0] > t_values[1] {
+            t_values.swap(0, 1);
+        } else if t_values[0] == t_values[1] {
+            t_count -= 1;
         }
     }
-    if q != 0.0 {
-        let t = q / a;
-        if t >= 0.0 && t <= 1.0 {
-            t_values[t_count] = t;
-            t_count += 1;
+    t_count
+}
+
+// translation of Skia's solve_cubic_poly
+/// Finds the real roots of _at³ + bt² + ct + d_, pinned to 0…1, ascending and without duplicates.
+pub(crate) fn find_unit_cubic_roots(a: Scalar, b: Scalar, c: Scalar, d: Scalar, t_values: &mut [Scalar; 3]) -> usize {
+    // SK_ScalarNearlyZero
+    if a.abs() <= 1.0 / 4096.0 {
+        // we're just a quadratic
+        let mut quad_roots = [0.0; 2];
+        let count = find_unit_quad_roots(b, c, d, &mut quad_roots);
+        t_values[..count].copy_from_slice(&quad_roots[..count]);
+        return count;
+    }
+    let (a, b, c) = (b / a, c / a, d / a);
+    let q = (a * a - b * 3.0) / 9.0;
+    let r = (2.0 * a * a * a - 9.0 * a * b + 27.0 * c) / 54.0;
+    let q3 = q * q * q;
+    let r2_minus_q3 = r * r - q3;
+    let adiv3 = a / 3.0;
+    if r2_minus_q3 < 0.0 {
+        // we have 3 real roots
+        // the divide/root can, due to finite precisions, be slightly outside of -1...1
+        let theta = (r / q3.sqrt()).clamp(-1.0, 1.0).acos();
+        let neg2_root_q = -2.0 * q.sqrt();
+        t_values[0] = (neg2_root_q * (theta / 3.0).cos() - adiv3).clamp(0.0, 1.0);
+        t_values[1] = (neg2_root_q * ((theta + 2.0 * PI) / 3.0).cos() - adiv3).clamp(0.0, 1.0);
+        t_values[2] = (neg2_root_q * ((theta - 2.0 * PI) / 3.0).cos() - adiv3).clamp(0.0, 1.0);
+        t_values.sort_by(|a, b| a.partial_cmp(b).expect("Roots are never NaN"));
+        let mut count = 1;
+        for i in 1..3 {
+            if t_values[i] != t_values[count - 1] {
+                t_values[count] = t_values[i];
+                count += 1;
+            }
+        }
+        count
+    } else {
+        // we have 1 real root
+        let mut root = (r.abs() + r2_minus_q3.sqrt()).cbrt();
+        if r > 0.0 {
+            root = -root;
+        }
+        if root != 0.0 {
+            root += q / root;
         }
+        t_values[0] = (root - adiv3).clamp(0.0, 1.0);
+        1
     }
-    return t_count;
+}
+
+/// The trait for segments whose extrema (where the derivative of x or y is zero) can be found.
+///
+/// Only extrema strictly between _t_=0 and _t_=1 are returned, as the endpoints are always on
+/// the curve anyway.
+pub trait Extrema {
+    /// Parameters of the extrema in x, ascending.
+    fn x_extrema(&self) -> Vec<Scalar>;
+    /// Parameters of the extrema in y, ascending.
+    fn y_extrema(&self) -> Vec<Scalar>;
+    /// Parameters of the extrema in either x or y, ascending and without duplicates.
+    fn extrema(&self) -> Vec<Scalar> {
+        let mut t_values = self.x_extrema();
+        t_values.extend(self.y_extrema());
+        t_values.sort_by(|a, b| a.partial_cmp(b).expect("Extrema are never NaN"));
+        t_values.dedup();
+        t_values
+    }
+}
+
+fn quadratic_deriv_extrema(coeff: [crate::Point; 3], axis: usize) -> Vec<Scalar> {
+    let mut t_values = [0.0; 2];
+    let roots = find_unit_quad_roots(coeff[0][axis], coeff[1][axis], coeff[2][axis], &mut t_values);
+    t_values[..roots].to_vec()
 }
 
 impl Extrema for Conic {
-    fn extrema(&self) -> f32 {
-        let coeff = self.deriv_coeff();
-        let mut tValues = [0f32, 0f32];
-        let roots = find_unit_quad_roots(coeff[0], coeff[1], coeff[2], &mut tValues);
+    fn x_extrema(&self) -> Vec<Scalar> {
+        quadratic_deriv_extrema(self.deriv_coeff(), 0)
+    }
+    fn y_extrema(&self) -> Vec<Scalar> {
+        quadratic_deriv_extrema(self.deriv_coeff(), 1)
+    }
+}
 
-        tValues[0]
+impl Extrema for Cubic {
+    fn x_extrema(&self) -> Vec<Scalar> {
+        quadratic_deriv_extrema(self.deriv_coeff(), 0)
+    }
+    fn y_extrema(&self) -> Vec<Scalar> {
+        quadratic_deriv_extrema(self.deriv_coeff(), 1)
+    }
+}
+
+fn linear_deriv_extrema(coeff: [crate::Point; 2], axis: usize) -> Vec<Scalar> {
+    let mut t = 0.0;
+    let roots = valid_unit_divide(-coeff[1][axis], coeff[0][axis], &mut t);
+    vec![t; roots]
+}
+
+impl Extrema for Quad {
+    fn x_extrema(&self) -> Vec<Scalar> {
+        linear_deriv_extrema(self.deriv_coeff(), 0)
+    }
+    fn y_extrema(&self) -> Vec<Scalar> {
+        linear_deriv_extrema(self.deriv_coeff(), 1)
     }
 }
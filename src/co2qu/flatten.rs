@@ -0,0 +1,224 @@
+//! Flattening of curves to polylines, after Raph Levien's “Flattening quadratic Béziers”:
+//! <https://raphlinus.github.io/graphics/curves/2019/12/23/flatten-quadbez.html>
+use super::eval::{DerivativeAt, EvalAt};
+use super::{Chop, Conic};
+use crate::cu2qu::CurveToQuadratic;
+use crate::types::{Cubic, Point, Quad, QuadSpline, Scalar};
+
+// Cubics and conics are first approximated by quads within this share of the tolerance; the rest
+// is left for flattening the quads.
+const QUAD_TOLERANCE_SHARE: Scalar = 0.1;
+
+/// An approximation of ∫ (1 + 4x²)^-¼ dx, the number of lines needed to flatten a parabola.
+fn approx_parabola_integral(x: Scalar) -> Scalar {
+    const D: Scalar = 0.67;
+    x / (1.0 - D + (D.powi(4) + 0.25 * x * x).sqrt().sqrt())
+}
+
+/// An approximation of the inverse of [`approx_parabola_integral`].
+fn approx_parabola_inv_integral(x: Scalar) -> Scalar {
+    const B: Scalar = 0.39;
+    x * (1.0 - B + (B * B + 0.25 * x * x).sqrt())
+}
+
+/// A quad mapped onto the parabola _y_ = _x_², from _x_=`x0` to `x2`.
+struct ParabolaMap {
+    a0: Scalar,
+    a2: Scalar,
+    u0: Scalar,
+    uscale: Scalar,
+    /// The (fractional) number of lines needed to flatten the quad, times the square root of the
+    /// tolerance.
+    val: Scalar,
+}
+
+impl ParabolaMap {
+    fn new(quad: &Quad, sqrt_tol: Scalar) -> Self {
+        let [p0, p1, p2] = *quad;
+        let (d01, d12) = (p1 - p0, p2 - p1);
+        let dd = d01 - d12;
+        let cross = (p2 - p0).perp(&dd);
+        let x0 = d01.dot(&dd) / cross;
+        let x2 = d12.dot(&dd) / cross;
+        let scale = (cross / (dd.norm() * (x2 - x0))).abs();
+        let (a0, a2) = (approx_parabola_integral(x0), approx_parabola_integral(x2));
+        let val = if cross != 0.0 && scale.is_finite() {
+            let da = (a2 - a0).abs();
+            let sqrt_scale = scale.sqrt();
+            if x0.signum() == x2.signum() {
+                da * sqrt_scale
+            } else {
+                // The quad includes the vertex of the parabola, its point of greatest curvature.
+                let xmin = sqrt_tol / sqrt_scale;
+                sqrt_tol * da / approx_parabola_integral(xmin)
+            }
+        } else {
+            0.0
+        };
+        let (u0, u2) = (approx_parabola_inv_integral(a0), approx_parabola_inv_integral(a2));
+        Self {
+            a0,
+            a2,
+            u0,
+            uscale: 1.0 / (u2 - u0),
+            val,
+        }
+    }
+
+    /// The parameter of the quad at the fraction `x` of the lines flattening it.
+    fn t_at(&self, x: Scalar) -> Scalar {
+        let a = self.a0 + (self.a2 - self.a0) * x;
+        (approx_parabola_inv_integral(a) - self.u0) * self.uscale
+    }
+}
+
+// Lines further from the curve than the tolerance are halved at most this many times.
+const MAX_LINE_HALVINGS: usize = 16;
+
+/// How far the quad strays from the line between its end points, at most. Each point of the quad
+/// is half as far from the line as the control point at most, and no further from the segment
+/// than from the line unless the control point lies beyond either end of it.
+fn chord_deviation(quad: &Quad) -> Scalar {
+    let [p0, p1, p2] = *quad;
+    let chord = p2 - p0;
+    let length_squared = chord.norm_squared();
+    let u = (p1 - p0).dot(&chord) / length_squared;
+    if (0.0..=1.0).contains(&u) {
+        chord.perp(&(p1 - p0)).abs() / length_squared.sqrt() / 2.0
+    } else {
+        (p1 - (p0 + p2) / 2.0).norm() / 2.0
+    }
+}
+
+/// Pushes the end of the line flattening `quad` from `t0` to `t1`, halving the line as long as the
+/// quad strays further from it than `tolerance`.
+fn push_line(quad: &Quad, t0: Scalar, t1: Scalar, tolerance: Scalar, halvings: usize, points: &mut Vec<Point>) {
+    let (start, end) = (quad.eval_at(t0), quad.eval_at(t1));
+    let piece = [start, start + quad.derivative_at(t0) * ((t1 - t0) / 2.0), end];
+    if halvings > 0 && chord_deviation(&piece) > tolerance {
+        let mid = (t0 + t1) / 2.0;
+        push_line(quad, t0, mid, tolerance, halvings - 1, points);
+        push_line(quad, mid, t1, tolerance, halvings - 1, points);
+    } else {
+        points.push(if t1 == 1.0 { quad[2] } else { end });
+    }
+}
+
+/// Flattens consecutive quads, sharing out the lines among them so that each line deviates from
+/// the curve by about the same amount. The approximations of the parabola integral can leave a
+/// line slightly further from the curve than the tolerance, so each line is checked and halved
+/// where it is.
+fn flatten_quads(quads: &[Quad], tolerance: Scalar) -> Vec<Point> {
+    let sqrt_tol = tolerance.sqrt();
+    let maps: Vec<ParabolaMap> = quads.iter().map(|quad| ParabolaMap::new(quad, sqrt_tol)).collect();
+    let total: Scalar = maps.iter().map(|map| map.val).sum();
+    let lines = (0.5 * total / sqrt_tol).ceil().max(1.0);
+    let mut points = vec![quads[0][0]];
+    let mut val_before = 0.0;
+    for (quad, map) in quads.iter().zip(&maps) {
+        let mut ts = vec![];
+        if map.val == 0.0 {
+            // A straight quad, which may still turn back on itself.
+            let [p0, p1, p2] = *quad;
+            let (d01, dd) = (p1 - p0, p0 - p1 * 2.0 + p2);
+            let t = -d01.dot(&dd) / dd.norm_squared();
+            if t > 0.0 && t < 1.0 {
+                ts.push(t);
+            }
+        } else {
+            // The lines falling on this quad, at whole multiples of the average line's val.
+            let first = (val_before * lines / total).floor() as usize + 1;
+            let last = ((val_before + map.val) * lines / total).ceil() as usize;
+            for i in first..last {
+                let x = (i as Scalar * total / lines - val_before) / map.val;
+                ts.push(map.t_at(x));
+            }
+        }
+        ts.push(1.0);
+        let mut t0 = 0.0;
+        for t1 in ts {
+            push_line(quad, t0, t1, tolerance, MAX_LINE_HALVINGS, &mut points);
+            t0 = t1;
+        }
+        val_before += map.val;
+    }
+    points
+}
+
+/// The trait for flattening curves to polylines which deviate from them by no more than a
+/// tolerance, using as few lines as possible.
+///
+/// The polyline starts and ends on the end points of the curve. Splines (`CubicSpline`,
+/// `QuadSpline`, `Vec<Conic>`) are flattened segment by segment into one polyline.
+pub trait Flatten {
+    fn flatten(&self, tolerance: Scalar) -> Vec<Point>;
+}
+
+impl Flatten for Quad {
+    fn flatten(&self, tolerance: Scalar) -> Vec<Point> {
+        flatten_quads(std::slice::from_ref(self), tolerance)
+    }
+}
+
+// We won't halve a cubic more than 8 times when cu2qu can't approximate it.
+const MAX_HALVINGS: usize = 8;
+
+/// Flattens a cubic into lines evenly spaced in _t_, as many as Wang's formula says are enough to
+/// stay within the tolerance. Far more lines are used than by flattening quads, so this is only
+/// for cubics cu2qu can't approximate.
+fn flatten_cubic_evenly(cubic: &Cubic, tolerance: Scalar) -> Vec<Point> {
+    let [p0, p1, p2, p3] = *cubic;
+    let second_difference = (p0 - p1 * 2.0 + p2).norm().max((p1 - p2 * 2.0 + p3).norm());
+    let lines = (0.75 * second_difference / tolerance).sqrt().ceil();
+    if !lines.is_finite() || lines <= 1.0 {
+        return vec![p0, p3];
+    }
+    let lines = lines as usize;
+    let mut points: Vec<Point> = (0..lines).map(|i| cubic.eval_at(i as Scalar / lines as Scalar)).collect();
+    points.push(p3);
+    points
+}
+
+fn flatten_cubic(cubic: &Cubic, tolerance: Scalar, halvings: usize) -> Vec<Point> {
+    let quad_tolerance = tolerance * QUAD_TOLERANCE_SHARE;
+    match cubic.curve_to_quadratic(quad_tolerance) {
+        Ok(quads) => flatten_quads(&quads, tolerance - quad_tolerance),
+        Err(_) if halvings == 0 => flatten_cubic_evenly(cubic, tolerance),
+        Err(_) => {
+            let [first, second] = cubic.chop();
+            let mut points = flatten_cubic(&first, tolerance, halvings - 1);
+            points.extend(flatten_cubic(&second, tolerance, halvings - 1).into_iter().skip(1));
+            points
+        }
+    }
+}
+
+/// Flattens quads approximating the cubic, halving it first wherever cu2qu fails. Pieces which
+/// still can't be approximated then are flattened evenly.
+impl Flatten for Cubic {
+    fn flatten(&self, tolerance: Scalar) -> Vec<Point> {
+        flatten_cubic(self, tolerance, MAX_HALVINGS)
+    }
+}
+
+/// Flattens the quads of [`Conic::as_quads`].
+impl Flatten for Conic {
+    fn flatten(&self, tolerance: Scalar) -> Vec<Point> {
+        let quad_tolerance = tolerance * QUAD_TOLERANCE_SHARE;
+        let quads: QuadSpline = self.as_quads(quad_tolerance);
+        flatten_quads(&quads, tolerance - quad_tolerance)
+    }
+}
+
+/// A joint is only included once, unless the spline has a gap there.
+impl<T: Flatten> Flatten for [T] {
+    fn flatten(&self, tolerance: Scalar) -> Vec<Point> {
+        let mut points: Vec<Point> = vec![];
+        for segment in self {
+            let polyline = segment.flatten(tolerance);
+            let skip = (points.last() == polyline.first()) as usize;
+            points.extend(polyline.into_iter().skip(skip));
+        }
+        points
+    }
+}
@@ -0,0 +1,233 @@
+use super::eval::DerivativeAt;
+use super::Conic;
+use crate::types::{Cubic, Quad, Scalar};
+
+/// Abscissae and weights of 5-point Gauss–Legendre quadrature on -1…1.
+const GAUSS_LEGENDRE_5: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+// We won't halve an interval more than 16 times while integrating, even near cusps.
+const MAX_DEPTH: usize = 16;
+
+// Newton's method usually converges in a few steps; bisection guarantees it in about 24.
+const MAX_ITERATIONS: usize = 50;
+
+#[cfg_attr(feature = "f64", allow(clippy::unnecessary_cast))]
+fn gauss_legendre(f: &dyn Fn(Scalar) -> Scalar, a: Scalar, b: Scalar) -> f64 {
+    let (mid, half) = ((a as f64 + b as f64) / 2f64, (b as f64 - a as f64) / 2f64);
+    GAUSS_LEGENDRE_5.iter().map(|(x, w)| w * f((mid + half * x) as Scalar) as f64).sum::<f64>() * half
+}
+
+fn integrate_adaptive(f: &dyn Fn(Scalar) -> Scalar, a: Scalar, b: Scalar, whole: f64, tolerance: f64, depth: usize) -> f64 {
+    let mid = (a + b) / 2.0;
+    let (left, right) = (gauss_legendre(f, a, mid), gauss_legendre(f, mid, b));
+    if depth == 0 || (left + right - whole).abs() <= tolerance {
+        return left + right;
+    }
+    integrate_adaptive(f, a, mid, left, tolerance / 2f64, depth - 1)
+        + integrate_adaptive(f, mid, b, right, tolerance / 2f64, depth - 1)
+}
+
+/// Integrates `f` (such as the speed) from 0 to `t`, halving intervals until the estimate is
+/// within `tolerance`.
+#[cfg_attr(feature = "f64", allow(clippy::unnecessary_cast))]
+pub(crate) fn integrate(f: &dyn Fn(Scalar) -> Scalar, t: Scalar, tolerance: Scalar) -> Scalar {
+    let whole = gauss_legendre(f, 0.0, t);
+    integrate_adaptive(f, 0.0, t, whole, tolerance as f64, MAX_DEPTH) as Scalar
+}
+
+/// The trait for measuring the length along a curve, as needed for text on a path, dashing and
+/// evenly spaced pattern brushes.
+///
+/// Lengths are found within an absolute error of `tolerance` (in units of the coordinates). For
+/// splines (`CubicSpline`, `QuadSpline`, `Vec<Conic>`), the parameter _t_ runs from 0 to the number
+/// of segments: its integer part is the index of the segment, and its fractional part the
+/// parameter along that segment.
+pub trait ArcLength {
+    /// The length of the whole curve.
+    fn arc_length(&self, tolerance: Scalar) -> Scalar;
+    /// The length of the curve from its start to _t_. A _t_ beyond either end of the curve is
+    /// taken to be that end.
+    fn length_at(&self, t: Scalar, tolerance: Scalar) -> Scalar;
+    /// The parameter _t_ at which the length from the start of the curve reaches `length`, so
+    /// that [`length_at`](Self::length_at) is its inverse. Lengths beyond either end of the curve
+    /// give the parameter of that end.
+    fn t_at_length(&self, length: Scalar, tolerance: Scalar) -> Scalar;
+}
+
+/// Solves `length_at(t) = length` for a segment of length `total` by Newton's method, falling back
+/// to bisection whenever a step would leave the bracket around the solution.
+fn segment_t_at_length<S: ArcLength + DerivativeAt>(segment: &S, length: Scalar, total: Scalar, tolerance: Scalar) -> Scalar {
+    if length <= 0.0 || total <= 0.0 {
+        return 0.0;
+    } else if length >= total {
+        return 1.0;
+    }
+    let (mut lo, mut hi) = (0.0, 1.0);
+    let mut t = length / total;
+    for _ in 0..MAX_ITERATIONS {
+        let error = segment.length_at(t, tolerance / 2.0) - length;
+        if error.abs() <= tolerance {
+            break;
+        }
+        if error < 0.0 {
+            lo = t;
+        } else {
+            hi = t;
+        }
+        let next = t - error / segment.derivative_at(t).norm();
+        t = if next > lo && next < hi { next } else { (lo + hi) / 2.0 };
+    }
+    t
+}
+
+impl ArcLength for Cubic {
+    fn arc_length(&self, tolerance: Scalar) -> Scalar {
+        self.length_at(1.0, tolerance)
+    }
+    fn length_at(&self, t: Scalar, tolerance: Scalar) -> Scalar {
+        integrate(&|t| self.derivative_at(t).norm(), t.clamp(0.0, 1.0), tolerance)
+    }
+    fn t_at_length(&self, length: Scalar, tolerance: Scalar) -> Scalar {
+        segment_t_at_length(self, length, self.arc_length(tolerance / 2.0), tolerance)
+    }
+}
+
+impl ArcLength for Quad {
+    fn arc_length(&self, tolerance: Scalar) -> Scalar {
+        self.length_at(1.0, tolerance)
+    }
+
+    /// Exact, save for rounding error; `tolerance` is unused.
+    #[cfg_attr(feature = "f64", allow(clippy::unnecessary_cast))]
+    fn length_at(&self, t: Scalar, _tolerance: Scalar) -> Scalar {
+        // The speed is √(At² + Bt + C), which has a closed form integral.
+        let [p0, p1, p2] = self.map(|p| p.map(|c| c as f64));
+        let (a, b) = (p0 - p1 * 2f64 + p2, (p1 - p0) * 2f64);
+        let (big_a, big_b, big_c) = (4f64 * a.dot(&a), 4f64 * a.dot(&b), b.dot(&b));
+        let t = t.clamp(0.0, 1.0) as f64;
+        if big_a <= f64::EPSILON * big_c {
+            // A line, at constant speed.
+            return (big_c.sqrt() * t) as Scalar;
+        }
+        let cross = a.perp(&b);
+        if cross * cross <= 1e-12 * a.norm_squared() * b.norm_squared() {
+            // Collinear points, with a speed of √A·|t - t₀| which may reach zero where the curve
+            // turns back on itself.
+            let t0 = -big_b / (2f64 * big_a);
+            let g = |t: f64| (t - t0) * (t - t0).abs() / 2f64;
+            return (big_a.sqrt() * (g(t) - g(0f64))) as Scalar;
+        }
+        let disc = 4f64 * big_a * big_c - big_b * big_b;
+        let root_a = big_a.sqrt();
+        let antiderivative = |t: f64| {
+            let speed = ((big_a * t + big_b) * t + big_c).sqrt();
+            let x = 2f64 * big_a * t + big_b;
+            // ln(2√A·S + X), rewritten as ln(disc) - ln(2√A·S - X) to avoid cancellation when
+            // X < 0.
+            let log = if x >= 0f64 {
+                (2f64 * root_a * speed + x).ln()
+            } else {
+                disc.ln() - (2f64 * root_a * speed - x).ln()
+            };
+            x * speed / (4f64 * big_a) + disc / (8f64 * big_a * root_a) * log
+        };
+        (antiderivative(t) - antiderivative(0f64)) as Scalar
+    }
+
+    fn t_at_length(&self, length: Scalar, tolerance: Scalar) -> Scalar {
+        segment_t_at_length(self, length, self.arc_length(tolerance / 2.0), tolerance)
+    }
+}
+
+impl ArcLength for Conic {
+    fn arc_length(&self, tolerance: Scalar) -> Scalar {
+        self.length_at(1.0, tolerance)
+    }
+    fn length_at(&self, t: Scalar, tolerance: Scalar) -> Scalar {
+        integrate(&|t| self.derivative_at(t).norm(), t.clamp(0.0, 1.0), tolerance)
+    }
+    fn t_at_length(&self, length: Scalar, tolerance: Scalar) -> Scalar {
+        segment_t_at_length(self, length, self.arc_length(tolerance / 2.0), tolerance)
+    }
+}
+
+/// The tolerance is shared out evenly among the segments.
+impl<T: ArcLength> ArcLength for [T] {
+    fn arc_length(&self, tolerance: Scalar) -> Scalar {
+        let tolerance = tolerance / self.len().max(1) as Scalar;
+        self.iter().map(|segment| segment.arc_length(tolerance)).sum()
+    }
+
+    fn length_at(&self, t: Scalar, tolerance: Scalar) -> Scalar {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let tolerance = tolerance / self.len() as Scalar;
+        let t = t.clamp(0.0, self.len() as Scalar);
+        let index = (t as usize).min(self.len() - 1);
+        let before: Scalar = self[..index].iter().map(|segment| segment.arc_length(tolerance)).sum();
+        before + self[index].length_at(t - index as Scalar, tolerance)
+    }
+
+    fn t_at_length(&self, length: Scalar, tolerance: Scalar) -> Scalar {
+        let tolerance = tolerance / self.len().max(1) as Scalar;
+        let mut remaining = length;
+        for (index, segment) in self.iter().enumerate() {
+            let segment_length = segment.arc_length(tolerance);
+            if remaining < segment_length || index + 1 == self.len() {
+                return index as Scalar + segment.t_at_length(remaining, tolerance);
+            }
+            remaining -= segment_length;
+        }
+        0.0
+    }
+}
+
+/// Finds where ascending lengths fall along a spline, measuring each segment just once and walking
+/// along the spline rather than starting over from its start every time.
+pub(crate) struct LengthWalker<'a, T> {
+    spline: &'a [T],
+    segment_lengths: Vec<Scalar>,
+    /// The segment the last length fell on, and the length of the spline before it.
+    index: usize,
+    before: Scalar,
+    /// The tolerance of each segment.
+    tolerance: Scalar,
+}
+
+impl<'a, T: ArcLength + DerivativeAt> LengthWalker<'a, T> {
+    /// The `spline` must not be empty. The tolerance is shared out evenly among its segments.
+    pub(crate) fn new(spline: &'a [T], tolerance: Scalar) -> Self {
+        let tolerance = tolerance / spline.len() as Scalar;
+        let segment_lengths = spline.iter().map(|segment| segment.arc_length(tolerance)).collect();
+        Self {
+            spline,
+            segment_lengths,
+            index: 0,
+            before: 0.0,
+            tolerance,
+        }
+    }
+
+    /// The length of the whole spline.
+    pub(crate) fn total(&self) -> Scalar {
+        self.segment_lengths.iter().sum()
+    }
+
+    /// The segment on which `length` falls, and the parameter along it. A length at the joint
+    /// between two segments falls at the start of the latter.
+    pub(crate) fn locate(&mut self, length: Scalar) -> (usize, Scalar) {
+        while self.index + 1 < self.spline.len() && length - self.before >= self.segment_lengths[self.index] {
+            self.before += self.segment_lengths[self.index];
+            self.index += 1;
+        }
+        let (segment, segment_length) = (&self.spline[self.index], self.segment_lengths[self.index]);
+        (self.index, segment_t_at_length(segment, length - self.before, segment_length, self.tolerance))
+    }
+}
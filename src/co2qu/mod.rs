@@ -3,19 +3,46 @@
 #[macro_use]
 pub(crate) mod pow2;
 
+mod bounds;
+pub use bounds::Bounds;
+mod budget;
+pub use budget::QuadBudget;
 mod chop;
-pub use chop::Chop;
+pub(crate) use chop::Hull;
+pub use chop::{Chop, ChopAtExtrema, ChopAtTValues, Endpoints};
 mod consts;
 pub use consts::MAX_QUAD_POW2;
+mod continuity;
+pub use continuity::{Continuity, Joint, Smooth};
+mod dash;
+pub use dash::Dash;
+mod deviation;
+pub use deviation::{area_difference, max_distance, Deviation};
 mod eval;
+pub use eval::{Curvature, DerivativeAt, EvalAt, EvalTangentAt};
+pub(crate) mod extrema;
+pub use extrema::Extrema;
+mod flatten;
+pub use flatten::Flatten;
+mod length;
+pub use length::ArcLength;
+mod nearest;
+pub use nearest::{Nearest, Projection};
 mod p3d;
 pub use pow2::{BelowQuadTolerance, QuadPowerOf2};
+mod resample;
+pub use resample::{Resample, Sample};
+mod soa;
+pub use soa::{ConicBatch, CubicBatch, QuadBatch, SegmentBatch};
+mod tolerance;
+pub use tolerance::{ToQuads, Tolerance};
+mod verify;
+pub use verify::{verify, Violation, MAX_TANGENT_ANGLE};
 
-use crate::p3d::Point3Like;
+use crate::cu2qu::ApproxNotFoundError;
 use crate::point;
-use crate::types::{Point, QuadSpline};
+use crate::types::{Point, Quad, QuadSpline, Scalar};
 
-use glifparser::PointLike;
 use log;
 
 use std::cmp;
@@ -34,11 +61,11 @@ pub struct Conic {
     pub control: Point,
     /// The weight of the conic. If _w_==1, parabolic. If _w_ < 1, elliptical. If _w_ > 1,
     /// hyperbolic.
-    pub weight: f32,
+    pub weight: Scalar,
 }
 
 impl Conic {
-    pub fn new(start: Point, control: Point, end: Point, weight: f32) -> Self {
+    pub fn new(start: Point, control: Point, end: Point, weight: Scalar) -> Self {
         Self {
             start,
             end,
@@ -69,31 +96,47 @@ impl Conic {
     }
 }
 
+/// Writes the quads of the `2^level` conics subdividing `conic` into `quads`, in order.
+fn subdivide_into(conic: &Conic, level: usize, quads: &mut [Quad]) {
+    if level == 0 {
+        quads[0] = [conic.start, conic.control, conic.end];
+        return;
+    }
+    let [first, second] = conic.chop();
+    let (before, after) = quads.split_at_mut(1 << (level - 1));
+    subdivide_into(&first, level - 1, before);
+    subdivide_into(&second, level - 1, after);
+}
+
 impl Conic {
     fn chop_into_quads_pow2(&self, pow2: &mut usize) -> QuadSpline {
+        let mut quads: QuadSpline = vec![[Point::default(); 3]; 1 << *pow2];
+        let quad_count = self.chop_into_quads_pow2_into(pow2, &mut quads);
+        quads.truncate(quad_count);
+        quads
+    }
+
+    /// Writes the quads into the start of `quads`, which must hold `2^pow2` of them, and returns
+    /// how many there are.
+    fn chop_into_quads_pow2_into(&self, pow2: &mut usize, quads: &mut [Quad]) -> usize {
         let quad_count = 1 << *pow2;
-        let mut quads: QuadSpline = vec![[Point::default(); 3]; quad_count];
-        let conics = self.subdivide(*pow2);
+        let quads = &mut quads[..quad_count];
+        subdivide_into(self, *pow2, quads);
         if *pow2 == MAX_QUAD_POW2 {
             // If an extreme weight generates many quads ...
-            let line_start = conics[0].start;
-            let line_end = conics[0].end;
-            let quad_start = conics[1].start;
-            if line_start == line_end && quad_start == conics[1].control {
+            let line_start = quads[0][0];
+            let line_end = quads[0][2];
+            let quad_start = quads[1][0];
+            if line_start == line_end && quad_start == quads[1][1] {
                 quads[0][1] = line_start;
                 quads[0][2] = line_start;
                 quads[1][0] = line_start; // set ctrl == end to make lines
-                quads[1][2] = conics[1].end;
                 *pow2 = 1;
-                return quads;
+                return 2;
             }
         }
-        for i in 0..conics.len() {
-            let conic = &conics[i];
-            quads[i] = [conic.start, conic.control, conic.end];
-        }
         for quad in quads.iter_mut() {
-            if quad.iter().any(|p| !f32::from(p.x()).is_finite() || !f32::from(p.y()).is_finite()) {
+            if quad.iter().any(|p| !p.iter().all(|c| c.is_finite())) {
                 // if we generated a non-finite, pin ourselves to the middle of the hull,
                 // as our first and last are already on the first/last pts of the hull.
                 for i in 1..3 {
@@ -101,20 +144,36 @@ impl Conic {
                 }
             }
         }
-        quads
+        quad_count
     }
 }
 
 /// Convert a “conic” (rational quadratic) Bézier curve to N quadratic spline segments.
 impl Conic {
-    pub fn as_quads(&self, tol: f32) -> QuadSpline {
+    /// `tol` is a distance in font units, as for
+    /// [`CurveToQuadratic`](crate::cu2qu::CurveToQuadratic): no point of the spline is farther than
+    /// it from the conic.
+    pub fn as_quads(&self, tol: Scalar) -> QuadSpline {
+        let mut quads: QuadSpline = vec![[Point::default(); 3]; 1 << self.quad_pow2(tol)];
+        let quad_count = self.as_quads_into(tol, &mut quads).expect("Room for as many quads as needed");
+        quads.truncate(quad_count);
+        quads
+    }
+
+    /// Like [`Conic::as_quads`], but writes the quads into the start of `quads` rather than
+    /// allocating them, and returns how many there are. Fails if more quads are needed than fit; a
+    /// buffer of `1 << MAX_QUAD_POW2` quads is always long enough.
+    pub fn as_quads_into(&self, tol: Scalar, quads: &mut [Quad]) -> Result<usize, ApproxNotFoundError> {
         let mut pow2 = self.quad_pow2(tol);
+        if quads.len() < 1 << pow2 {
+            return Err(ApproxNotFoundError);
+        }
         let orig_pow2 = pow2;
-        let ret = self.chop_into_quads_pow2(&mut pow2);
+        let quad_count = self.chop_into_quads_pow2_into(&mut pow2, quads);
         log::debug!("Tolerance {} yielded QuadSpline of len {}", tol, pow2);
         if orig_pow2 != pow2 {
             log::warn!("Tolerance {} caused lines to be generated, not quads", tol);
         }
-        ret
+        Ok(quad_count)
     }
 }
@@ -0,0 +1,200 @@
+use super::eval::EvalAt;
+use super::extrema::find_unit_cubic_roots;
+use super::Conic;
+use crate::types::{Cubic, Point, Quad, Scalar};
+
+/// The point on a curve nearest to another point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Projection {
+    /// The parameter of the nearest point. For splines, the index of the segment plus the
+    /// parameter along it, as in [`ArcLength`](super::ArcLength).
+    pub t: Scalar,
+    pub point: Point,
+    /// The distance from the point projected to the curve.
+    pub distance: Scalar,
+}
+
+/// The trait for projecting points onto curves, as needed to snap to a curve or highlight it on
+/// hover. The nearest point may be an end point of the curve.
+///
+/// Where several points are equally near (e.g. the centre of a circle), any of them may be
+/// returned.
+pub trait Nearest {
+    /// Panics if a spline is empty.
+    fn nearest(&self, p: Point) -> Projection;
+}
+
+fn projection<S: EvalAt>(segment: &S, p: Point, t: Scalar) -> Projection {
+    let point = segment.eval_at(t);
+    Projection {
+        t,
+        point,
+        distance: (point - p).norm(),
+    }
+}
+
+fn nearer(a: Projection, b: Projection) -> Projection {
+    if b.distance < a.distance {
+        b
+    } else {
+        a
+    }
+}
+
+// Roots are refined until a step moves them less than this.
+const T_TOLERANCE: f64 = 1e-12;
+
+/// A polynomial in _t_ of degree 5 at most, in f64 as the coefficients of a quintic lose too much
+/// precision in f32. The constant coefficient comes first.
+type Polynomial = [f64; 6];
+
+fn eval(poly: &Polynomial, t: f64) -> f64 {
+    poly.iter().rev().fold(0.0, |sum, &c| sum * t + c)
+}
+
+fn derivative(poly: &Polynomial) -> Polynomial {
+    std::array::from_fn(|i| if i < 5 { (i + 1) as f64 * poly[i + 1] } else { 0.0 })
+}
+
+/// Terms of degree over 5 are dropped, but none of the products here have any.
+fn mul(a: &Polynomial, b: &Polynomial) -> Polynomial {
+    std::array::from_fn(|k| (0..=k).map(|i| a[i] * b[k - i]).sum())
+}
+
+fn add(a: &Polynomial, b: &Polynomial) -> Polynomial {
+    std::array::from_fn(|i| a[i] + b[i])
+}
+
+fn sub(a: &Polynomial, b: &Polynomial) -> Polynomial {
+    std::array::from_fn(|i| a[i] - b[i])
+}
+
+/// Ascending parameters including 0, 1 and every root of `poly` between them, between each two of
+/// which `poly` is monotonic. The roots of the derivative split 0…1 into intervals on which the
+/// polynomial is monotonic, so each of its roots is found by Newton's method within the interval
+/// it changes sign in, falling back to bisection whenever a step would leave it. There may be
+/// other parameters too, which are harmless as candidates for the nearest point.
+fn unit_roots_and_splits(poly: &Polynomial) -> Vec<f64> {
+    if poly[1..].iter().all(|&c| c == 0.0) {
+        return vec![0.0, 1.0];
+    }
+    let slope = derivative(poly);
+    let splits = unit_roots_and_splits(&slope);
+    let mut t_values = vec![0.0];
+    for pair in splits.windows(2) {
+        let (mut lo, mut hi) = (pair[0], pair[1]);
+        let at_lo = eval(poly, lo);
+        if at_lo * eval(poly, hi) < 0.0 {
+            let mut t = (lo + hi) / 2.0;
+            loop {
+                let value = eval(poly, t);
+                if value == 0.0 {
+                    break;
+                } else if (value < 0.0) == (at_lo < 0.0) {
+                    lo = t;
+                } else {
+                    hi = t;
+                }
+                let mut next = t - value / eval(&slope, t);
+                if !(next > lo && next < hi) {
+                    next = (lo + hi) / 2.0;
+                    if next <= lo || next >= hi {
+                        break;
+                    }
+                }
+                if (next - t).abs() <= T_TOLERANCE {
+                    break;
+                }
+                t = next;
+            }
+            t_values.push(t);
+        }
+        t_values.push(pair[1]);
+    }
+    t_values
+}
+
+/// Finds the nearest point of the rational curve _P_ = `x`/`w`, `y`/`w` among the ends and the
+/// roots of (_P_ − `p`)·_P′_, or rather of (_N_ − `p`_w_)·(_N′w_ − _Nw′_) which has the same roots
+/// but is a polynomial (of degree 5 for a cubic, whose `w` is 1, and of degree 4 for a conic).
+#[cfg_attr(feature = "f64", allow(clippy::unnecessary_cast))]
+fn nearest_rational<S: EvalAt>(segment: &S, p: Point, [x, y]: [Polynomial; 2], w: Polynomial) -> Projection {
+    let dw = derivative(&w);
+    let [qx, qy] = [(x, p[0]), (y, p[1])].map(|(axis, c)| sub(&axis, &w.map(|w| w * c as f64)));
+    let dot = [qx, qy].iter().fold([0.0; 6], |dot, q| {
+        let dq = sub(&mul(&derivative(q), &w), &mul(q, &dw));
+        add(&dot, &mul(q, &dq))
+    });
+    let distance_squared = |t: f64| (eval(&qx, t).powi(2) + eval(&qy, t).powi(2)) / eval(&w, t).powi(2);
+    let t = unit_roots_and_splits(&dot)
+        .into_iter()
+        .min_by(|&a, &b| distance_squared(a).total_cmp(&distance_squared(b)))
+        .expect("Ends are candidates");
+    projection(segment, p, t as Scalar)
+}
+
+/// The nearest point is found exactly, save for rounding error, by isolating the roots of a
+/// quintic.
+impl Nearest for Cubic {
+    #[cfg_attr(feature = "f64", allow(clippy::unnecessary_cast))]
+    fn nearest(&self, p: Point) -> Projection {
+        let [p0, p1, p2, p3] = self.map(|q| [q[0] as f64, q[1] as f64]);
+        let axis = |i: usize| {
+            [
+                p0[i],
+                3.0 * (p1[i] - p0[i]),
+                3.0 * (p0[i] - 2.0 * p1[i] + p2[i]),
+                p3[i] - p0[i] + 3.0 * (p1[i] - p2[i]),
+                0.0,
+                0.0,
+            ]
+        };
+        nearest_rational(self, p, [axis(0), axis(1)], [1.0, 0.0, 0.0, 0.0, 0.0, 0.0])
+    }
+}
+
+impl Nearest for Quad {
+    /// The nearest point is found exactly, as (_P_ − `p`)·_P′_ is only a cubic polynomial.
+    fn nearest(&self, p: Point) -> Projection {
+        let [p0, p1, p2] = *self;
+        let (a, b, c) = (p0 - p1 * 2.0 + p2, (p1 - p0) * 2.0, p0 - p);
+        let mut t_values = [0.0; 3];
+        let roots =
+            find_unit_cubic_roots(2.0 * a.dot(&a), 3.0 * a.dot(&b), b.dot(&b) + 2.0 * a.dot(&c), b.dot(&c), &mut t_values);
+        t_values[..roots]
+            .iter()
+            .chain(&[0.0, 1.0])
+            .map(|&t| projection(self, p, t))
+            .reduce(nearer)
+            .expect("Ends are candidates")
+    }
+}
+
+/// The nearest point is found exactly, save for rounding error, by isolating the roots of a
+/// quartic.
+impl Nearest for Conic {
+    #[cfg_attr(feature = "f64", allow(clippy::unnecessary_cast))]
+    fn nearest(&self, p: Point) -> Projection {
+        let [p0, p1, p2] = [self.start, self.control, self.end].map(|q| [q[0] as f64, q[1] as f64]);
+        let weight = self.weight as f64;
+        let axis = |i: usize| [p0[i], 2.0 * (weight * p1[i] - p0[i]), p0[i] - 2.0 * weight * p1[i] + p2[i], 0.0, 0.0, 0.0];
+        let w = [1.0, 2.0 * (weight - 1.0), 2.0 - 2.0 * weight, 0.0, 0.0, 0.0];
+        nearest_rational(self, p, [axis(0), axis(1)], w)
+    }
+}
+
+impl<T: Nearest> Nearest for [T] {
+    fn nearest(&self, p: Point) -> Projection {
+        self.iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                let projection = segment.nearest(p);
+                Projection {
+                    t: index as Scalar + projection.t,
+                    ..projection
+                }
+            })
+            .reduce(nearer)
+            .expect("Cannot project onto an empty spline")
+    }
+}
@@ -1,19 +1,17 @@
-use glifparser::PointLike;
-
-use crate::p3d::{Point3, Point3Like};
-use crate::{point, Point};
+use crate::p3d::Point3;
+use crate::{point, Point, Scalar};
 
 #[inline]
-pub fn ratquad_map<PL: PointLike>(src: &[PL; 3], w: f32) -> [Point3; 3] {
+pub fn ratquad_map(src: &[Point; 3], w: Scalar) -> [Point3; 3] {
     [
-        Point3::new(src[0].x(), src[0].y() * 1f32, 1f32),
-        Point3::new(src[1].x() * w, src[1].y() * w, w),
-        Point3::new(src[2].x(), src[2].y() * 1f32, 1f32),
+        Point3::new(src[0][0], src[0][1], 1.0),
+        Point3::new(src[1][0] * w, src[1][1] * w, w),
+        Point3::new(src[2][0], src[2][1], 1.0),
     ]
 }
 
 #[inline]
-pub fn interp(src: &[Point3; 3], t: f32) -> [Point3; 3] {
+pub fn interp(src: &[Point3; 3], t: Scalar) -> [Point3; 3] {
     let (src0, src1, src2) = (src[0].as_linalg(), src[1].as_linalg(), src[2].as_linalg());
     let ab = src0.lerp(&src1, t);
     let bc = src1.lerp(&src2, t);
@@ -25,6 +23,6 @@ impl Into<super::Point> for Point3 {
     // from Skia project_down
     // static SkPoint project_down(const SkPoint3& src) { return {src.fX / src.fZ, src.fY / src.fZ}; }
     fn into(self) -> super::Point {
-        point![f32::from(self.x() / self.z()), f32::from(self.y() / self.z())]
+        point![self.x / self.z, self.y / self.z]
     }
 }
@@ -0,0 +1,69 @@
+use super::chop::Endpoints;
+use super::eval::{DerivativeAt, EvalAt, EvalTangentAt};
+use super::length::{ArcLength, LengthWalker};
+use crate::types::{Point, Scalar};
+
+/// A point sampled along a spline.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sample {
+    /// The parameter of the point, as in [`ArcLength`]: the index of the segment plus the
+    /// parameter along it.
+    pub t: Scalar,
+    pub point: Point,
+    /// The unit tangent at the point, in the direction of travel. Where the curve has none, as at a
+    /// cusp or on a segment whose points all coincide, it is the direction from the start of the
+    /// segment to its end, or else of the nearest segment after or before it which has a length.
+    /// It is zero only if the whole spline is a single point.
+    pub tangent: Point,
+}
+
+/// The trait for sampling points evenly spaced by arc length along a spline (`CubicSpline`,
+/// `QuadSpline`, `Vec<Conic>`), as needed for patterns along a path. Samples are spaced across
+/// segment boundaries as if the spline were a single curve.
+///
+/// A single segment can be sampled as a spline of one, using [`std::slice::from_ref`].
+pub trait Resample {
+    /// `count` samples from the start to the end of the spline, both included, with equal lengths
+    /// between consecutive ones. A `count` of 1 gives just the start.
+    fn resample(&self, count: usize, tolerance: Scalar) -> Vec<Sample>;
+    /// A sample every `distance` units along the spline, starting at its start. The end is only
+    /// included if the length of the spline is a multiple of `distance`.
+    ///
+    /// Panics if `distance` is not positive.
+    fn resample_every(&self, distance: Scalar, tolerance: Scalar) -> Vec<Sample>;
+}
+
+fn sample<T: Endpoints + EvalAt + EvalTangentAt>(spline: &[T], (index, t): (usize, Scalar)) -> Sample {
+    let segment = &spline[index];
+    let chords = spline[index..].iter().chain(spline[..index].iter().rev()).map(|segment| segment.end() - segment.start());
+    let tangent = std::iter::once(segment.eval_tangent_at(t)).chain(chords).find(|tangent| tangent.norm_squared() > 0.0);
+    Sample {
+        t: index as Scalar + t,
+        point: segment.eval_at(t),
+        tangent: tangent.map_or(Point::default(), |tangent| Point(tangent.normalize())),
+    }
+}
+
+impl<T: ArcLength + DerivativeAt + Endpoints + EvalAt + EvalTangentAt> Resample for [T] {
+    fn resample(&self, count: usize, tolerance: Scalar) -> Vec<Sample> {
+        if self.is_empty() {
+            return vec![];
+        }
+        let mut walker = LengthWalker::new(self, tolerance);
+        let total = walker.total();
+        let spacing = total / (count.max(2) - 1) as Scalar;
+        // The last sample is at the very end, despite rounding error in the spacing.
+        let lengths = (0..count).map(|i| if i + 1 == count && i > 0 { total } else { i as Scalar * spacing });
+        lengths.map(|length| sample(self, walker.locate(length))).collect()
+    }
+
+    fn resample_every(&self, distance: Scalar, tolerance: Scalar) -> Vec<Sample> {
+        assert!(distance > 0.0, "Samples must be a positive distance apart");
+        if self.is_empty() {
+            return vec![];
+        }
+        let mut walker = LengthWalker::new(self, tolerance);
+        let count = (walker.total() / distance + Scalar::EPSILON * 4.0).floor() as usize + 1;
+        (0..count).map(|i| sample(self, walker.locate(i as Scalar * distance))).collect()
+    }
+}
@@ -0,0 +1,443 @@
+//! Batches of segments stored as a structure of arrays, for evaluating thousands of them at once.
+//!
+//! Each coordinate of each control point is kept in an array of its own, and the segments are
+//! worked on `LANES` at a time in fixed-size arrays of `Scalar`, which the compiler turns into SIMD
+//! instructions where the target has them, without needing `std::simd`.
+use super::Conic;
+use crate::point;
+use crate::types::{Cubic, Point, Quad, Rect, Scalar};
+
+use std::ops::{Add, Div, Mul, Sub};
+
+/// The number of segments worked on at once.
+const LANES: usize = 8;
+
+/// One coordinate of `LANES` segments.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Lanes([Scalar; LANES]);
+
+impl Lanes {
+    fn splat(x: Scalar) -> Self {
+        Lanes([x; LANES])
+    }
+
+    /// Loads the first `LANES` values of `src`, padding with zeroes if there are fewer.
+    fn load(src: &[Scalar]) -> Self {
+        let mut lanes = [0.0; LANES];
+        let count = src.len().min(LANES);
+        lanes[..count].copy_from_slice(&src[..count]);
+        Lanes(lanes)
+    }
+
+    /// Stores as many lanes as fit into the start of `dst`.
+    fn store(self, dst: &mut [Scalar]) {
+        let count = dst.len().min(LANES);
+        dst[..count].copy_from_slice(&self.0[..count]);
+    }
+
+    fn zip(self, other: Self, f: impl Fn(Scalar, Scalar) -> Scalar) -> Self {
+        Lanes(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+    }
+
+    fn min(self, other: Self) -> Self {
+        self.zip(other, Scalar::min)
+    }
+
+    fn max(self, other: Self) -> Self {
+        self.zip(other, Scalar::max)
+    }
+
+    fn sqrt(self) -> Self {
+        Lanes(self.0.map(Scalar::sqrt))
+    }
+
+    /// As nalgebra's `lerp`, so that chopping gives the same points as [`Chop`](super::Chop).
+    fn lerp(self, other: Self, t: Self) -> Self {
+        self * (Lanes::splat(1.0) - t) + other * t
+    }
+}
+
+macro_rules! lanes_op {
+    ($trait:ident, $fn:ident, $op:tt) => {
+        impl $trait for Lanes {
+            type Output = Lanes;
+            fn $fn(self, other: Lanes) -> Lanes {
+                self.zip(other, |a, b| a $op b)
+            }
+        }
+    };
+}
+
+lanes_op!(Add, add, +);
+lanes_op!(Sub, sub, -);
+lanes_op!(Mul, mul, *);
+lanes_op!(Div, div, /);
+
+/// Evaluates the polynomial Bézier with control points `p` at `t` by de Casteljau's algorithm.
+fn de_casteljau<const N: usize>(mut p: [Lanes; N], t: Lanes) -> Lanes {
+    for level in 1..N {
+        for i in 0..N - level {
+            p[i] = p[i].lerp(p[i + 1], t);
+        }
+    }
+    p[0]
+}
+
+/// Chops the polynomial Bézier with control points `p` at `t`, as de Casteljau's algorithm does.
+fn de_casteljau_chop<const N: usize>(mut p: [Lanes; N], t: Lanes) -> [[Lanes; N]; 2] {
+    let (mut left, mut right) = (p, p);
+    for level in 1..N {
+        for i in 0..N - level {
+            p[i] = p[i].lerp(p[i + 1], t);
+        }
+        left[level] = p[0];
+        right[N - 1 - level] = p[N - 1 - level];
+    }
+    [left, right]
+}
+
+/// The parameter of the root, if it lies strictly between 0 and 1; else 0, where the segment's
+/// end point is anyway.
+fn unit_or_zero(t: Scalar) -> Scalar {
+    if t > 0.0 && t < 1.0 {
+        t
+    } else {
+        0.0
+    }
+}
+
+/// The roots of _at² + bt + c_ strictly between 0 and 1, as
+/// [`find_unit_quad_roots`](super::extrema::find_unit_quad_roots) finds them, with 0 in place of
+/// the missing ones.
+#[cfg_attr(feature = "f64", allow(clippy::unnecessary_cast))]
+fn unit_quad_roots(a: Lanes, b: Lanes, c: Lanes) -> [Lanes; 2] {
+    let mut roots = [Lanes::splat(0.0); 2];
+    for i in 0..LANES {
+        let (a, b, c) = (a.0[i], b.0[i], c.0[i]);
+        if a == 0.0 {
+            roots[0].0[i] = unit_or_zero(-c / b);
+            continue;
+        }
+        let dr = (b as f64) * (b as f64) - 4f64 * (a as f64) * (c as f64);
+        let r = dr.sqrt() as Scalar;
+        let q = if b < 0.0 { -(b - r) / 2.0 } else { -(b + r) / 2.0 };
+        roots[0].0[i] = unit_or_zero(q / a);
+        roots[1].0[i] = unit_or_zero(c / q);
+    }
+    roots
+}
+
+/// The control points of a batch of segments with `N` of them, one array per coordinate.
+#[derive(Clone, Debug, PartialEq)]
+struct Controls<const N: usize> {
+    x: [Vec<Scalar>; N],
+    y: [Vec<Scalar>; N],
+}
+
+impl<const N: usize> Default for Controls<N> {
+    fn default() -> Self {
+        Self {
+            x: std::array::from_fn(|_| Vec::new()),
+            y: std::array::from_fn(|_| Vec::new()),
+        }
+    }
+}
+
+impl<const N: usize> Controls<N> {
+    fn len(&self) -> usize {
+        self.x[0].len()
+    }
+
+    fn push(&mut self, points: &[Point; N]) {
+        for (i, p) in points.iter().enumerate() {
+            self.x[i].push(p[0]);
+            self.y[i].push(p[1]);
+        }
+    }
+
+    fn get(&self, index: usize) -> [Point; N] {
+        std::array::from_fn(|i| point![self.x[i][index], self.y[i][index]])
+    }
+
+    /// The x and y coordinates of the segments from `start` on, `LANES` at a time.
+    fn lanes(&self, start: usize) -> ([Lanes; N], [Lanes; N]) {
+        (
+            std::array::from_fn(|i| Lanes::load(&self.x[i][start..])),
+            std::array::from_fn(|i| Lanes::load(&self.y[i][start..])),
+        )
+    }
+
+    /// Appends `count` segments from lanes.
+    fn extend(&mut self, x: &[Lanes; N], y: &[Lanes; N], count: usize) {
+        for i in 0..N {
+            self.x[i].extend_from_slice(&x[i].0[..count]);
+            self.y[i].extend_from_slice(&y[i].0[..count]);
+        }
+    }
+
+    /// The starts of the chunks of `LANES` segments, and the number of segments in each.
+    fn chunks(&self) -> impl Iterator<Item = (usize, usize)> {
+        let len = self.len();
+        (0..len).step_by(LANES).map(move |start| (start, (len - start).min(LANES)))
+    }
+
+    /// The bounding boxes of the control points.
+    fn fast_bounds(&self) -> Vec<Rect> {
+        let mut rects = Vec::with_capacity(self.len());
+        for (start, count) in self.chunks() {
+            let (x, y) = self.lanes(start);
+            let x = (x.into_iter().reduce(Lanes::min).unwrap(), x.into_iter().reduce(Lanes::max).unwrap());
+            let y = (y.into_iter().reduce(Lanes::min).unwrap(), y.into_iter().reduce(Lanes::max).unwrap());
+            push_rects(&mut rects, x, y, count);
+        }
+        rects
+    }
+}
+
+/// Appends `count` rectangles from the lanes of their (min, max) x and y coordinates.
+fn push_rects(rects: &mut Vec<Rect>, x: (Lanes, Lanes), y: (Lanes, Lanes), count: usize) {
+    rects.extend((0..count).map(|i| Rect::new(point![x.0 .0[i], y.0 .0[i]], point![x.1 .0[i], y.1 .0[i]])));
+}
+
+/// The (min, max) of one coordinate of a segment with end points `start` and `end` and the point
+/// `at` its extrema in that coordinate.
+fn axis_bounds(start: Lanes, end: Lanes, at: [Lanes; 2]) -> (Lanes, Lanes) {
+    let (min, max) = (start.min(end), start.max(end));
+    (min.min(at[0]).min(at[1]), max.max(at[0]).max(at[1]))
+}
+
+/// The trait for batches of segments (`CubicBatch`, `QuadBatch`, `ConicBatch`), which do to all of
+/// their segments at once what [`EvalAt`](super::EvalAt), [`Chop`](super::Chop) and
+/// [`Bounds`](super::Bounds) do to one.
+pub trait SegmentBatch: Default + FromIterator<Self::Segment> {
+    type Segment;
+
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    fn push(&mut self, segment: &Self::Segment);
+    /// The segment at `index`.
+    ///
+    /// Panics if `index` is out of bounds.
+    fn get(&self, index: usize) -> Self::Segment;
+
+    /// Evaluates every segment at `t`, writing the x and y coordinates into the start of `xs` and
+    /// `ys`.
+    ///
+    /// Panics if `xs` or `ys` is shorter than the batch.
+    fn eval_at_into(&self, t: Scalar, xs: &mut [Scalar], ys: &mut [Scalar]);
+    /// The point of every segment at `t`, in order.
+    fn eval_at(&self, t: Scalar) -> Vec<Point> {
+        let (mut xs, mut ys) = (vec![0.0; self.len()], vec![0.0; self.len()]);
+        self.eval_at_into(t, &mut xs, &mut ys);
+        xs.into_iter().zip(ys).map(|(x, y)| point![x, y]).collect()
+    }
+    /// Chops every segment at `t`, into a batch of the parts from 0 to `t` and one of the parts
+    /// from `t` to 1.
+    fn chop_at(&self, t: Scalar) -> [Self; 2];
+    /// The bounding box of the control points of every segment.
+    fn fast_bounds(&self) -> Vec<Rect>;
+    /// The smallest bounding box of every segment.
+    fn tight_bounds(&self) -> Vec<Rect>;
+}
+
+macro_rules! polynomial_batch {
+    ($(#[$attr:meta])* $name:ident, $segment:ty, $n:literal, $extrema:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug, Default, PartialEq)]
+        pub struct $name(Controls<$n>);
+
+        impl FromIterator<$segment> for $name {
+            fn from_iter<I: IntoIterator<Item = $segment>>(iter: I) -> Self {
+                let mut batch = Self::default();
+                iter.into_iter().for_each(|segment| batch.push(&segment));
+                batch
+            }
+        }
+
+        impl SegmentBatch for $name {
+            type Segment = $segment;
+
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            fn push(&mut self, segment: &$segment) {
+                self.0.push(segment)
+            }
+
+            fn get(&self, index: usize) -> $segment {
+                self.0.get(index)
+            }
+
+            fn eval_at_into(&self, t: Scalar, xs: &mut [Scalar], ys: &mut [Scalar]) {
+                assert!(xs.len() >= self.len() && ys.len() >= self.len(), "Room for every point");
+                let t = Lanes::splat(t);
+                for (start, count) in self.0.chunks() {
+                    let (x, y) = self.0.lanes(start);
+                    de_casteljau(x, t).store(&mut xs[start..start + count]);
+                    de_casteljau(y, t).store(&mut ys[start..start + count]);
+                }
+            }
+
+            fn chop_at(&self, t: Scalar) -> [Self; 2] {
+                let [mut first, mut second] = [Self::default(), Self::default()];
+                let t = Lanes::splat(t);
+                for (start, count) in self.0.chunks() {
+                    let (x, y) = self.0.lanes(start);
+                    let ([x0, x1], [y0, y1]) = (de_casteljau_chop(x, t), de_casteljau_chop(y, t));
+                    first.0.extend(&x0, &y0, count);
+                    second.0.extend(&x1, &y1, count);
+                }
+                [first, second]
+            }
+
+            fn fast_bounds(&self) -> Vec<Rect> {
+                self.0.fast_bounds()
+            }
+
+            fn tight_bounds(&self) -> Vec<Rect> {
+                let mut rects = Vec::with_capacity(self.len());
+                for (start, count) in self.0.chunks() {
+                    let (x, y) = self.0.lanes(start);
+                    let bounds = |p: [Lanes; $n]| {
+                        let at = $extrema(&p).map(|t| de_casteljau(p, t));
+                        axis_bounds(p[0], p[$n - 1], at)
+                    };
+                    push_rects(&mut rects, bounds(x), bounds(y), count);
+                }
+                rects
+            }
+        }
+    };
+}
+
+/// The parameters of the extrema of one coordinate of a cubic, as
+/// [`Extrema`](super::Extrema) finds them, with 0 in place of the missing ones.
+fn cubic_extrema(&[p0, p1, p2, p3]: &[Lanes; 4]) -> [Lanes; 2] {
+    let (three, six) = (Lanes::splat(3.0), Lanes::splat(6.0));
+    let a = three * (p3 - p0 + three * (p1 - p2));
+    let b = six * (p0 - p1 * Lanes::splat(2.0) + p2);
+    let c = three * (p1 - p0);
+    unit_quad_roots(a, b, c)
+}
+
+/// The parameter of the extremum of one coordinate of a quad, or 0 if it has none.
+fn quad_extrema(&[p0, p1, p2]: &[Lanes; 3]) -> [Lanes; 2] {
+    let a = p0 - p1 * Lanes::splat(2.0) + p2;
+    let t = (p0 - p1).zip(a, |numer, denom| unit_or_zero(numer / denom));
+    [t, Lanes::splat(0.0)]
+}
+
+polynomial_batch!(
+    /// A batch of cubics.
+    CubicBatch,
+    Cubic,
+    4,
+    cubic_extrema
+);
+polynomial_batch!(
+    /// A batch of quads.
+    QuadBatch,
+    Quad,
+    3,
+    quad_extrema
+);
+
+/// A batch of conics.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConicBatch {
+    controls: Controls<3>,
+    weights: Vec<Scalar>,
+}
+
+impl ConicBatch {
+    /// The conics from `start` on, `LANES` at a time, as homogeneous x, y and z coordinates.
+    fn homogeneous(&self, start: usize) -> ([Lanes; 3], [Lanes; 3], [Lanes; 3]) {
+        let ([x0, x1, x2], [y0, y1, y2]) = self.controls.lanes(start);
+        let (one, w) = (Lanes::splat(1.0), Lanes::load(&self.weights[start..]));
+        ([x0, x1 * w, x2], [y0, y1 * w, y2], [one, w, one])
+    }
+}
+
+impl FromIterator<Conic> for ConicBatch {
+    fn from_iter<I: IntoIterator<Item = Conic>>(iter: I) -> Self {
+        let mut batch = Self::default();
+        iter.into_iter().for_each(|conic| batch.push(&conic));
+        batch
+    }
+}
+
+impl SegmentBatch for ConicBatch {
+    type Segment = Conic;
+
+    fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    fn push(&mut self, conic: &Conic) {
+        self.controls.push(&[conic.start, conic.control, conic.end]);
+        self.weights.push(conic.weight);
+    }
+
+    fn get(&self, index: usize) -> Conic {
+        let [start, control, end] = self.controls.get(index);
+        Conic::new(start, control, end, self.weights[index])
+    }
+
+    fn eval_at_into(&self, t: Scalar, xs: &mut [Scalar], ys: &mut [Scalar]) {
+        assert!(xs.len() >= self.len() && ys.len() >= self.len(), "Room for every point");
+        let t = Lanes::splat(t);
+        for (start, count) in self.controls.chunks() {
+            let (x, y, z) = self.homogeneous(start);
+            let z = de_casteljau(z, t);
+            (de_casteljau(x, t) / z).store(&mut xs[start..start + count]);
+            (de_casteljau(y, t) / z).store(&mut ys[start..start + count]);
+        }
+    }
+
+    /// Chops in homogeneous coordinates, as [`Chop`](super::Chop) does.
+    fn chop_at(&self, t: Scalar) -> [Self; 2] {
+        let [mut first, mut second] = [Self::default(), Self::default()];
+        let t = Lanes::splat(t);
+        for (start, count) in self.controls.chunks() {
+            let (x, y, z) = self.homogeneous(start);
+            let ([x0, x1], [y0, y1], [z0, z1]) = (de_casteljau_chop(x, t), de_casteljau_chop(y, t), de_casteljau_chop(z, t));
+            // The middle point is where both parts meet, and its weight normalizes the others.
+            let root = z0[2].sqrt();
+            let (x0, y0) = ([x0[0], x0[1] / z0[1], x0[2] / z0[2]], [y0[0], y0[1] / z0[1], y0[2] / z0[2]]);
+            let (x1, y1) = ([x1[0] / z1[0], x1[1] / z1[1], x1[2]], [y1[0] / z1[0], y1[1] / z1[1], y1[2]]);
+            first.controls.extend(&x0, &y0, count);
+            second.controls.extend(&x1, &y1, count);
+            first.weights.extend_from_slice(&(z0[1] / root).0[..count]);
+            second.weights.extend_from_slice(&(z1[1] / root).0[..count]);
+        }
+        [first, second]
+    }
+
+    fn fast_bounds(&self) -> Vec<Rect> {
+        self.controls.fast_bounds()
+    }
+
+    fn tight_bounds(&self) -> Vec<Rect> {
+        let mut rects = Vec::with_capacity(self.len());
+        for (start, count) in self.controls.chunks() {
+            let ([x0, x1, x2], [y0, y1, y2]) = self.controls.lanes(start);
+            let (_, _, z) = self.homogeneous(start);
+            let w = z[1];
+            let bounds = |p0: Lanes, p1: Lanes, p2: Lanes| {
+                // The coefficients of the numerator of the derivative, as for `deriv_coeff`.
+                let two = Lanes::splat(2.0);
+                let (p20, p10) = (p2 - p0, p1 - p0);
+                let a = two * (w * p20 - p20);
+                let b = two * (p20 - two * w * p10);
+                let c = two * w * p10;
+                let at = unit_quad_roots(a, b, c).map(|t| de_casteljau([p0, p1 * w, p2], t) / de_casteljau(z, t));
+                axis_bounds(p0, p2, at)
+            };
+            push_rects(&mut rects, bounds(x0, x1, x2), bounds(y0, y1, y2), count);
+        }
+        rects
+    }
+}
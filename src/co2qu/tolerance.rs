@@ -0,0 +1,77 @@
+use super::bounds::Bounds;
+use super::Conic;
+use crate::cu2qu::{ApproxNotFoundError, CurveToQuadratic};
+use crate::types::{Cubic, QuadSpline, Scalar};
+
+/// The least a relative tolerance stands for, in units in the last place of the segment's
+/// coordinates. Any nearer, and rounding error may keep an approximation from being found at all,
+/// as for a tiny segment far from the origin, or one which is a single point and whose bounding
+/// box has no diagonal.
+const MIN_RELATIVE_ULPS: Scalar = 16.0;
+
+/// How far an approximation may stray from the curve it approximates.
+///
+/// An absolute tolerance suits one font, but not fonts of other units per em, nor curves of very
+/// different sizes: tiny ones get approximated too coarsely, and huge ones too finely. The other
+/// kinds scale with the font or with the curve.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Tolerance {
+    /// A distance in font units, as taken by [`CurveToQuadratic`] and [`Conic::as_quads`].
+    Absolute(Scalar),
+    /// A fraction of the font's units per em, e.g. 0.001 for one unit at 1000 UPM.
+    Upm { fraction: Scalar, units_per_em: Scalar },
+    /// A fraction of the diagonal of the segment's (tight) bounding box, but no less than a few
+    /// units in the last place of its coordinates.
+    Relative(Scalar),
+}
+
+impl Tolerance {
+    /// The absolute tolerance, in font units, to approximate `segment` with.
+    pub fn absolute_for<S: Bounds + ?Sized>(&self, segment: &S) -> Scalar {
+        match *self {
+            Tolerance::Absolute(distance) => distance,
+            Tolerance::Upm { fraction, units_per_em } => fraction * units_per_em,
+            Tolerance::Relative(fraction) => {
+                let bounds = segment.tight_bounds();
+                let magnitude = bounds.min.iter().chain(bounds.max.iter()).fold(0.0, |most: Scalar, c| most.max(c.abs()));
+                (fraction * bounds.diagonal()).max(MIN_RELATIVE_ULPS * Scalar::EPSILON * magnitude)
+            }
+        }
+    }
+}
+
+impl From<Scalar> for Tolerance {
+    fn from(distance: Scalar) -> Self {
+        Tolerance::Absolute(distance)
+    }
+}
+
+/// The trait for converting curves to quads within a [`Tolerance`] of any kind.
+pub trait ToQuads {
+    fn to_quads(&self, tolerance: Tolerance) -> Result<QuadSpline, ApproxNotFoundError>;
+}
+
+impl ToQuads for Cubic {
+    fn to_quads(&self, tolerance: Tolerance) -> Result<QuadSpline, ApproxNotFoundError> {
+        self.curve_to_quadratic(tolerance.absolute_for(self))
+    }
+}
+
+/// Never fails, as [`Conic::as_quads`] doesn't.
+impl ToQuads for Conic {
+    fn to_quads(&self, tolerance: Tolerance) -> Result<QuadSpline, ApproxNotFoundError> {
+        Ok(self.as_quads(tolerance.absolute_for(self)))
+    }
+}
+
+/// Splines (`CubicSpline`, `Vec<Conic>`) are converted segment by segment, and a relative tolerance
+/// is relative to each segment.
+impl<T: ToQuads> ToQuads for [T] {
+    fn to_quads(&self, tolerance: Tolerance) -> Result<QuadSpline, ApproxNotFoundError> {
+        let mut spline = QuadSpline::new();
+        for segment in self {
+            spline.extend(segment.to_quads(tolerance)?);
+        }
+        Ok(spline)
+    }
+}
@@ -0,0 +1,134 @@
+use super::chop::Endpoints;
+use super::continuity::{Continuity, Joint};
+use super::deviation::{max_distance, Deviation};
+use super::eval::{EvalAt, EvalTangentAt};
+use super::nearest::Nearest;
+use crate::Scalar;
+use std::{error::Error, fmt};
+
+/// The greatest angle, in radians, allowed between tangents which ought to be the same: at the
+/// joints of the approximation, and between its ends and those of the original. It is lenient
+/// enough for control points rounded to whole font units.
+pub const MAX_TANGENT_ANGLE: Scalar = 0.01;
+
+// End points which ought to be the same may be apart by this share of the tolerance, for rounding
+// error in the converter.
+const GAP_TOLERANCE_SHARE: Scalar = 1e-3;
+
+/// The first way in which an approximation was found to fail [`verify`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Violation {
+    /// The approximation has no segments.
+    Empty,
+    /// The approximation doesn't start at the start of the original, but `gap` away.
+    StartMoved { gap: Scalar },
+    /// The approximation doesn't end at the end of the original, but `gap` away.
+    EndMoved { gap: Scalar },
+    /// The worst joint of the approximation which isn't G1 continuous, and the parameter on the
+    /// original nearest to it.
+    Discontinuity { joint: Joint, t: Scalar },
+    /// The approximation starts at `angle` radians from the original's start tangent.
+    StartTangent { angle: Scalar },
+    /// The approximation ends at `angle` radians from the original's end tangent.
+    EndTangent { angle: Scalar },
+    /// The approximation strays farther than the tolerance from the original.
+    TooFar(Deviation),
+}
+
+impl Violation {
+    /// The parameter on the original segment where the approximation is at fault.
+    pub fn t(&self) -> Scalar {
+        match self {
+            Violation::Empty | Violation::StartMoved { .. } | Violation::StartTangent { .. } => 0.0,
+            Violation::EndMoved { .. } | Violation::EndTangent { .. } => 1.0,
+            Violation::Discontinuity { t, .. } => *t,
+            Violation::TooFar(deviation) => deviation.t,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Violation: ")?;
+        match self {
+            Violation::Empty => write!(f, "The approximation is empty"),
+            Violation::StartMoved { gap } => write!(f, "The approximation starts {} away from the original", gap),
+            Violation::EndMoved { gap } => write!(f, "The approximation ends {} away from the original", gap),
+            Violation::Discontinuity { joint, t } => write!(
+                f,
+                "The approximation is discontinuous after segment {} (gap {}, angle {}), near t={}",
+                joint.index, joint.gap, joint.angle, t
+            ),
+            Violation::StartTangent { angle } => write!(f, "The start tangent is off by {} radians", angle),
+            Violation::EndTangent { angle } => write!(f, "The end tangent is off by {} radians", angle),
+            Violation::TooFar(deviation) => {
+                write!(f, "The approximation is {} away from the original at t={}", deviation.distance, deviation.t)
+            }
+        }
+    }
+}
+
+impl Error for Violation {
+    fn description(&self) -> &str {
+        "Approximation violates its tolerance"
+    }
+}
+
+/// The angle between the tangents of `a` at `a_t` and `b` at `b_t`, from 0 to π.
+fn angle_between<U: EvalTangentAt, V: EvalTangentAt>(a: &U, a_t: Scalar, b: &V, b_t: Scalar) -> Scalar {
+    let (u, v) = (a.eval_tangent_at(a_t), b.eval_tangent_at(b_t));
+    u.perp(&v).atan2(u.dot(&v)).abs()
+}
+
+/// Check that the spline `approximation` is a faithful conversion of `original`, within
+/// `tolerance` font units: that it starts and ends on the original's end points, with the same
+/// tangents; that its segments join smoothly; and that no point of either curve is farther than
+/// `tolerance` from the other.
+///
+/// This works for any converter's output, e.g. a cubic and the quads of cu2qu, a conic and those of
+/// [`Conic::as_quads`](super::Conic::as_quads), or a quad and the cubic of qu2cu, whether or not
+/// they came from this crate. The checks are made in the order above, and the first to fail is
+/// returned.
+pub fn verify<S, A>(original: &S, approximation: &[A], tolerance: Scalar) -> Result<(), Violation>
+where
+    S: Endpoints + EvalAt + EvalTangentAt + Nearest,
+    A: Endpoints + EvalAt + EvalTangentAt + Nearest,
+    [A]: Continuity,
+{
+    let (first, last) = match (approximation.first(), approximation.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Err(Violation::Empty),
+    };
+    let gap_tolerance = tolerance * GAP_TOLERANCE_SHARE;
+    let gap = (first.start() - original.start()).norm();
+    if gap > gap_tolerance {
+        return Err(Violation::StartMoved { gap });
+    }
+    let gap = (last.end() - original.end()).norm();
+    if gap > gap_tolerance {
+        return Err(Violation::EndMoved { gap });
+    }
+    let worst_joint = approximation
+        .joints(false)
+        .into_iter()
+        .filter(|joint| !joint.is_g1(gap_tolerance, MAX_TANGENT_ANGLE))
+        // A gap is worse than a kink.
+        .max_by(|a, b| a.gap.total_cmp(&b.gap).then(a.angle.total_cmp(&b.angle)));
+    if let Some(joint) = worst_joint {
+        let t = original.nearest(approximation[joint.index].end()).t;
+        return Err(Violation::Discontinuity { joint, t });
+    }
+    let angle = angle_between(original, 0.0, first, 0.0);
+    if angle > MAX_TANGENT_ANGLE {
+        return Err(Violation::StartTangent { angle });
+    }
+    let angle = angle_between(original, 1.0, last, 1.0);
+    if angle > MAX_TANGENT_ANGLE {
+        return Err(Violation::EndTangent { angle });
+    }
+    let deviation = max_distance(original, approximation);
+    if deviation.distance > tolerance {
+        return Err(Violation::TooFar(deviation));
+    }
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use crate::point;
-use crate::types::Point;
+use crate::types::{Point, Scalar};
 
 /// A pub trait for types of curves we can transform to and from coefficients
 pub trait Coefficient {
@@ -12,9 +12,9 @@ pub trait Coefficient {
 /// A conic section curve with a real quadratic coeff numerator and a real quadratic coeff denominator
 pub struct ConicCoeff {
     /// The quadratic coefficient form of the point
-    numer: QuadCoeff,
+    pub(crate) numer: QuadCoeff,
     /// The quadratic coefficient form of the "weight" of the curve
-    denom: QuadCoeff,
+    pub(crate) denom: QuadCoeff,
 }
 
 impl Coefficient for ConicCoeff {
@@ -65,11 +65,11 @@ pub trait FromWeightedSegment<const SEGMENT_LEN: usize, const WEIGHT_LEN: usize>
     /// @param segment the segment of the curve to convert
     /// @param w the weight of the curve
     /// @return a coefficient type
-    fn from_segment(segment: [Point; SEGMENT_LEN], w: [f32; WEIGHT_LEN]) -> Self;
+    fn from_segment(segment: [Point; SEGMENT_LEN], w: [Scalar; WEIGHT_LEN]) -> Self;
 }
 
 impl FromWeightedSegment<3, 1> for ConicCoeff {
-    fn from_segment(segment: [Point; 3], w: [f32; 1]) -> Self {
+    fn from_segment(segment: [Point; 3], w: [Scalar; 1]) -> Self {
         let p0 = segment[0];
         let p1 = segment[1];
         let p2 = segment[2];
@@ -77,12 +77,12 @@ impl FromWeightedSegment<3, 1> for ConicCoeff {
 
         let p1w = p1 * w;
         let numer_c = p0;
-        let numer_a = p2 - (p1w * 2f32) + p0;
-        let numer_b = (p1w - p0) * 2f32;
+        let numer_a = p2 - (p1w * 2.0) + p0;
+        let numer_b = (p1w - p0) * 2.0;
 
-        let denom_c = point![1f32, 1f32];
-        let denom_b = (point![w, w] - denom_c) * 2f32;
-        let denom_a = point![0f32, 0f32] - denom_b;
+        let denom_c = point![1.0, 1.0];
+        let denom_b = (point![w, w] - denom_c) * 2.0;
+        let denom_a = point![0.0, 0.0] - denom_b;
 
         ConicCoeff {
             numer: QuadCoeff {
@@ -107,9 +107,9 @@ impl FromSegment<4> for CubicCoeff {
         let p3 = segment[3];
 
         CubicCoeff {
-            a: p3 + 3f32 * (p1 - p2) - p0,
-            b: 3f32 * (p2 - (p1 * 2f32) + p0),
-            c: 3f32 * (p1 - p0),
+            a: p3 + 3.0 * (p1 - p2) - p0,
+            b: 3.0 * (p2 - (p1 * 2.0) + p0),
+            c: 3.0 * (p1 - p0),
             d: p0,
         }
     }
@@ -122,8 +122,8 @@ impl FromSegment<3> for QuadCoeff {
         let p2 = segment[2];
 
         QuadCoeff {
-            a: p2 - (p1 * 2f32) + p0,
-            b: (p1 - p0) * 2f32,
+            a: p2 - (p1 * 2.0) + p0,
+            b: (p1 - p0) * 2.0,
             c: p0,
         }
     }
@@ -137,8 +137,8 @@ pub trait ToSegment<const SEGMENT_LEN: usize> {
 impl ToSegment<4> for CubicCoeff {
     fn to_segment(&self) -> [Point; 4] {
         let p0 = self.d;
-        let p1 = (self.c / 3f32) + self.d;
-        let p2 = (self.b + self.c) / 3f32 + p1;
+        let p1 = (self.c / 3.0) + self.d;
+        let p2 = (self.b + self.c) / 3.0 + p1;
         let p3 = self.a + self.d + self.c + self.b;
         [p0, p1, p2, p3]
     }
@@ -147,7 +147,7 @@ impl ToSegment<4> for CubicCoeff {
 impl ToSegment<3> for QuadCoeff {
     fn to_segment(&self) -> [Point; 3] {
         let p0 = self.c;
-        let p1 = (self.b / 2f32) + self.c;
+        let p1 = (self.b / 2.0) + self.c;
         let p2 = self.a + self.b + self.c;
         [p0, p1, p2]
     }
@@ -180,22 +180,22 @@ impl Coefficient for CubicConicCoeff {
 }
 
 impl FromWeightedSegment<4, 2> for CubicConicCoeff {
-    fn from_segment(segment: [Point; 4], w: [f32; 2]) -> Self {
+    fn from_segment(segment: [Point; 4], w: [Scalar; 2]) -> Self {
         let [p0, p1, p2, p3] = segment;
         let [w0, w1] = w;
 
         let p1w = p1 * w0;
         let p2w = p2 * w1;
 
-        let numer_a = p3 + 3f32 * (p1w - p2w) - p0;
-        let numer_b = 3f32 * (p2w - (p1w * 2f32) + p0);
-        let numer_c = 3f32 * (p1w - p0);
+        let numer_a = p3 + 3.0 * (p1w - p2w) - p0;
+        let numer_b = 3.0 * (p2w - (p1w * 2.0) + p0);
+        let numer_c = 3.0 * (p1w - p0);
         let numer_d = p0;
 
-        let denom_a = point![0f32, 0f32];
-        let denom_b = (point![w0, w1] - denom_a) * 3f32;
-        let denom_c = (point![0f32, 0f32] - denom_b) * 3f32;
-        let denom_d = point![1f32, 1f32];
+        let denom_a = point![0.0, 0.0];
+        let denom_b = (point![w0, w1] - denom_a) * 3.0;
+        let denom_c = (point![0.0, 0.0] - denom_b) * 3.0;
+        let denom_d = point![1.0, 1.0];
 
         CubicConicCoeff {
             numer: CubicCoeff {
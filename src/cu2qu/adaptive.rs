@@ -0,0 +1,214 @@
+//! Adaptive splitting of cubics into quads.
+//!
+//! A spline of quads whose on-curve points are implied, halfway between their off-curve points, is
+//! a uniform quadratic B-spline. cu2qu places its off-curve points by a rule of thumb which only
+//! works for equal parts of the cubic; here they are fitted by least squares instead, so that the
+//! parts can be of any length, and the parts are lengthened where the quads are near the cubic and
+//! shortened where they are far from it.
+use super::{fewest_quads, min_quads, ApproxNotFoundError, CubicApprox, CubicFarthestFitInside, MAX_N};
+use crate::co2qu::{ChopAtTValues, EvalAt, EvalTangentAt};
+use crate::point;
+use crate::types::{Cubic, Point, Quad, QuadSpline, Scalar};
+
+// We won't move the split parameters more than 8 times for any n
+const MAX_ADAPTATIONS: usize = 8;
+
+// Number of points per quad at which it is fitted to, and compared with, its part of the cubic
+const SAMPLES: usize = 8;
+
+/// Like [`CurveToQuadratic::curve_to_quadratic`](super::CurveToQuadratic::curve_to_quadratic),
+/// but trying adaptive splits for each number of quads for which equal parts fail.
+///
+/// [`min_quads`] bounds the number from below here too: however the cubic is split, its longest
+/// part is at least 1/_n_ of it, and no quad follows that part's cubic term any nearer.
+pub(super) fn curve_to_quadratic(cubic: &Cubic, max_err: Scalar) -> Result<QuadSpline, ApproxNotFoundError> {
+    let (_, spline) = fewest_quads(min_quads(cubic, max_err), MAX_N, |n| {
+        cubic.approx_spline(n, max_err).or_else(|_| approx_spline_adaptive(cubic, n, max_err)).ok()
+    })
+    .ok_or(ApproxNotFoundError)?;
+    Ok(spline)
+}
+
+/// Approximates the cubic by `n` quads, moving the parameters at which it is split until every
+/// quad is within `tolerance` of its part. The error of a quad fitted to a short part of a cubic
+/// grows with the cube of the part's length, so each part is scaled by the cube root of its share
+/// of the error.
+fn approx_spline_adaptive(cubic: &Cubic, n: usize, tolerance: Scalar) -> Result<QuadSpline, ApproxNotFoundError> {
+    if n < 2 {
+        return Err(ApproxNotFoundError);
+    }
+    let mut lengths: Vec<Scalar> = vec![1.0 / n as Scalar; n];
+    for _ in 0..MAX_ADAPTATIONS {
+        let t_values: Vec<Scalar> = lengths[..n - 1]
+            .iter()
+            .scan(0.0, |t, length| {
+                *t += length;
+                Some(*t)
+            })
+            .collect();
+        let pieces = cubic.chop_at_t_values(&t_values);
+        let quads = fit(cubic, &pieces).ok_or(ApproxNotFoundError)?;
+        if quads.iter().zip(&pieces).all(|(quad, piece)| fits_inside(quad, piece, tolerance)) {
+            return Ok(quads);
+        }
+        let errors: Vec<Scalar> = quads.iter().zip(&pieces).map(|(quad, piece)| parametric_error(quad, piece)).collect();
+        let mean = errors.iter().sum::<Scalar>() / n as Scalar;
+        for (length, error) in lengths.iter_mut().zip(errors) {
+            *length *= (mean / error.max(mean * 1e-3)).cbrt();
+        }
+        let total: Scalar = lengths.iter().sum();
+        lengths.iter_mut().for_each(|length| *length /= total);
+    }
+    Err(ApproxNotFoundError)
+}
+
+/// Whether no point of the quad is farther than `tolerance` from the point of the cubic at the
+/// same parameter, by the same test as cu2qu's. That test takes the ends to be within the
+/// tolerance, as they are in cu2qu, but the implied on-curve points here are off the cubic, so
+/// they are checked first.
+fn fits_inside(quad: &Quad, cubic: &Cubic, tolerance: Scalar) -> bool {
+    let [q0, q1, q2] = *quad;
+    let (start, end) = (q0 - cubic[0], q2 - cubic[3]);
+    start.norm() <= tolerance
+        && end.norm() <= tolerance
+        && [start, q0 + (q1 - q0) * (2.0 / 3.0) - cubic[1], q2 + (q1 - q2) * (2.0 / 3.0) - cubic[2], end]
+            .farthest_fit_inside(tolerance)
+}
+
+/// The greatest distance between points of the quad and the cubic at the same parameter.
+fn parametric_error(quad: &Quad, cubic: &Cubic) -> Scalar {
+    (0..=SAMPLES)
+        .map(|i| {
+            let t = i as Scalar / SAMPLES as Scalar;
+            (quad.eval_at(t) - cubic.eval_at(t)).norm()
+        })
+        .fold(0.0, Scalar::max)
+}
+
+/// An off-curve point as a function of the unknowns of the fit: a constant, plus a multiple of up
+/// to two unknowns.
+#[derive(Copy, Clone)]
+struct Control {
+    constant: Point,
+    terms: [(usize, Point); 2],
+}
+
+/// Fits a spline of quads with implied on-curve points to the consecutive `pieces` of `cubic`, one
+/// quad per piece, in the least squares sense. The first and last off-curve points stay on the
+/// cubic's end tangents, so that the spline leaves and enters the cubic's end points as it does.
+///
+/// Returns `None` if the fit is degenerate, e.g. because the cubic has no end tangent.
+fn fit(cubic: &Cubic, pieces: &[Cubic]) -> Option<QuadSpline> {
+    let n = pieces.len();
+    let (start, end) = (cubic[0], cubic[3]);
+    let (start_tangent, end_tangent) = (cubic.eval_tangent_at(0.0), cubic.eval_tangent_at(1.0));
+    // The unknowns are how far along the end tangents the first and last off-curve points are,
+    // and the coordinates of the others.
+    let unknowns = 2 * n - 2;
+    let (x, y) = (point![1.0, 0.0], point![0.0, 1.0]);
+    let controls: Vec<Control> = (0..n)
+        .map(|i| {
+            if i == 0 {
+                Control {
+                    constant: start,
+                    terms: [(0, start_tangent), (0, Point::default())],
+                }
+            } else if i == n - 1 {
+                Control {
+                    constant: end,
+                    terms: [(unknowns - 1, end_tangent * -1.0), (0, Point::default())],
+                }
+            } else {
+                Control {
+                    constant: Point::default(),
+                    terms: [(2 * i - 1, x), (2 * i, y)],
+                }
+            }
+        })
+        .collect();
+
+    // Accumulate the normal equations, AᵀA·u = Aᵀb.
+    let mut ata = vec![vec![0.0; unknowns]; unknowns];
+    let mut atb = vec![0.0; unknowns];
+    for (i, piece) in pieces.iter().enumerate() {
+        for k in 0..=SAMPLES {
+            let s = k as Scalar / SAMPLES as Scalar;
+            // The weights of the on-curve points and the off-curve point of quad i at s.
+            let (w0, w1, w2) = ((1.0 - s) * (1.0 - s), 2.0 * s * (1.0 - s), s * s);
+            let mut weights: Vec<(usize, Scalar)> = vec![(i, w1)];
+            let mut constant = Point::default();
+            if i == 0 {
+                constant = constant + start * w0;
+            } else {
+                weights.extend([(i - 1, w0 / 2.0), (i, w0 / 2.0)]);
+            }
+            if i == n - 1 {
+                constant = constant + end * w2;
+            } else {
+                weights.extend([(i, w2 / 2.0), (i + 1, w2 / 2.0)]);
+            }
+            // The (sparse) rows of A for the x and y coordinates of the point.
+            let mut terms: Vec<(usize, Point)> = vec![];
+            for (j, w) in weights {
+                let control = controls[j];
+                constant = constant + control.constant * w;
+                terms.extend(control.terms.iter().map(|&(unknown, direction)| (unknown, direction * w)));
+            }
+            let target = piece.eval_at(s) - constant;
+            for &(a, row_a) in &terms {
+                atb[a] += row_a.dot(&target);
+                for &(b, row_b) in &terms {
+                    ata[a][b] += row_a.dot(&row_b);
+                }
+            }
+        }
+    }
+    let u = solve(ata, atb)?;
+
+    let off_curve: Vec<Point> = controls
+        .iter()
+        .map(|control| control.terms.iter().fold(control.constant, |p, &(unknown, direction)| p + direction * u[unknown]))
+        .collect();
+    Some(
+        (0..n)
+            .map(|i| {
+                let q0 = if i == 0 { start } else { (off_curve[i - 1] + off_curve[i]) * 0.5 };
+                let q2 = if i == n - 1 { end } else { (off_curve[i] + off_curve[i + 1]) * 0.5 };
+                [q0, off_curve[i], q2]
+            })
+            .collect(),
+    )
+}
+
+/// Solves the linear system `a`·_u_ = `b` by Gaussian elimination with partial pivoting. Returns
+/// `None` if `a` is singular.
+fn solve(mut a: Vec<Vec<Scalar>>, mut b: Vec<Scalar>) -> Option<Vec<Scalar>> {
+    let n = b.len();
+    let scale = a.iter().flatten().fold(0.0 as Scalar, |max, x| max.max(x.abs()));
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() <= scale * Scalar::EPSILON {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let (above, below) = a.split_at_mut(col + 1);
+        let pivot_row = &above[col];
+        for (row, target) in below.iter_mut().enumerate().map(|(i, target)| (col + 1 + i, target)) {
+            let factor = target[col] / pivot_row[col];
+            if factor == 0.0 {
+                continue;
+            }
+            for (x, p) in target[col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut u = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: Scalar = (row + 1..n).map(|k| a[row][k] * u[k]).sum();
+        u[row] = (b[row] - sum) / a[row][row];
+    }
+    u.iter().all(|u| u.is_finite()).then_some(u)
+}
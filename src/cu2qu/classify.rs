@@ -0,0 +1,180 @@
+use crate::co2qu::extrema::find_unit_quad_roots;
+use crate::co2qu::{Curvature, EvalTangentAt};
+use crate::types::{Cubic, Scalar};
+
+/// The shape of a cubic, as determined by its inflection function. Mirrors Skia's `SkCubicType`.
+///
+/// The classification concerns the whole (infinite) curve, so e.g. a `Loop` cubic only crosses
+/// itself between _t_=0 and _t_=1 if [`ClassifyCubic::self_intersection`] says so.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CubicType {
+    /// Two inflection points, like an “S”.
+    Serpentine,
+    /// The curve crosses itself.
+    Loop,
+    /// The curve has a cusp, where its derivative is zero.
+    LocalCusp,
+    /// One inflection point; the cusp is at _t_=∞.
+    CuspAtInfinity,
+    /// The cubic is a degree-elevated quadratic.
+    Quadratic,
+    /// All four points are collinear.
+    LineOrPoint,
+}
+
+/// Returns a positive power of 2 that when multiplied by `n` (and excepting the possibility of
+/// underflow) results in a value between 1 and 2 inclusive.
+fn previous_inverse_pow2(n: f64) -> f64 {
+    let mut bits = n.to_bits();
+    bits = (((1023u64 * 2) << 52) + ((1u64 << 52) - 1)) - bits; // exp=-exp
+    bits &= 0x7ffu64 << 52; // mantissa=1.0, sign=0
+    f64::from_bits(bits)
+}
+
+/// Writes the homogeneous roots _t_/_s_ ordered so that `t[0]/s[0] <= t[1]/s[1]`.
+fn write_cubic_inflection_roots(t0: f64, s0: f64, t1: f64, s1: f64, t: &mut [f64; 2], s: &mut [f64; 2]) {
+    t[0] = t0;
+    s[0] = s0;
+    // This copysign/abs business orients the implicit function so positive values are always on
+    // the "left" side of the curve.
+    t[1] = -t1.copysign(t1 * s1);
+    s[1] = -s1.abs();
+    // Ensure t[0]/s[0] <= t[1]/s[1] (s[1] is negative from above).
+    if s[1].copysign(s[0]) * t[0] > -s[0].abs() * t[1] {
+        t.swap(0, 1);
+        s.swap(0, 1);
+    }
+}
+
+// translation of SkClassifyCubic
+/// Classifies the cubic, also returning the homogeneous parameters _t_/_s_ of its inflections
+/// (serpentine), double point (loop) or cusp.
+///
+/// See “Resolution Independent Curve Rendering using Programmable Graphics Hardware”, 4.2 Curve
+/// Categorization and 4.4 Integral Cubics:
+/// <https://www.microsoft.com/en-us/research/wp-content/uploads/2005/01/p1000-loop.pdf>
+#[cfg_attr(feature = "f64", allow(clippy::unnecessary_cast))]
+fn classify_cubic(cubic: &Cubic) -> (CubicType, [f64; 2], [f64; 2]) {
+    let p: Vec<(f64, f64)> = cubic.iter().map(|p| (p[0] as f64, p[1] as f64)).collect();
+    // Find the cubic's inflection function, I = [T^3  -3T^2  3T  -1] dot D. (D0 will always be 0
+    // for integral cubics.)
+    let a1 = p[0].0 * (p[3].1 - p[2].1) + p[0].1 * (p[2].0 - p[3].0) + p[3].0 * p[2].1 - p[3].1 * p[2].0;
+    let a2 = p[1].0 * (p[0].1 - p[3].1) + p[1].1 * (p[3].0 - p[0].0) + p[0].0 * p[3].1 - p[0].1 * p[3].0;
+    let a3 = p[2].0 * (p[1].1 - p[0].1) + p[2].1 * (p[0].0 - p[1].0) + p[1].0 * p[0].1 - p[1].1 * p[0].0;
+
+    let mut d3 = 3f64 * a3;
+    let mut d2 = d3 - a2;
+    let mut d1 = d2 - a2 + a1;
+
+    // Shift the exponents in D so the largest magnitude falls somewhere in 1..2. This protects us
+    // from overflow down the road while solving for roots.
+    let norm = previous_inverse_pow2(d1.abs().max(d2.abs()).max(d3.abs()));
+    d1 *= norm;
+    d2 *= norm;
+    d3 *= norm;
+
+    let (mut t, mut s) = ([0f64; 2], [0f64; 2]);
+    let kind = if d1 != 0.0 {
+        let discr = 3f64 * d2 * d2 - 4f64 * d1 * d3;
+        if discr > 0.0 {
+            let q = 3f64 * d2 + (3f64 * discr).sqrt().copysign(d2);
+            write_cubic_inflection_roots(q, 6f64 * d1, 2f64 * d3, q, &mut t, &mut s);
+            CubicType::Serpentine
+        } else if discr < 0.0 {
+            let q = d2 + (-discr).sqrt().copysign(d2);
+            write_cubic_inflection_roots(q, 2f64 * d1, 2f64 * (d2 * d2 - d3 * d1), d1 * q, &mut t, &mut s);
+            CubicType::Loop
+        } else {
+            write_cubic_inflection_roots(d2, 2f64 * d1, d2, 2f64 * d1, &mut t, &mut s);
+            CubicType::LocalCusp
+        }
+    } else if d2 != 0.0 {
+        write_cubic_inflection_roots(d3, 3f64 * d2, 1f64, 0f64, &mut t, &mut s); // T1=infinity.
+        CubicType::CuspAtInfinity
+    } else {
+        write_cubic_inflection_roots(1f64, 0f64, 1f64, 0f64, &mut t, &mut s); // T0=T1=infinity.
+        if d3 != 0.0 {
+            CubicType::Quadratic
+        } else {
+            CubicType::LineOrPoint
+        }
+    };
+    (kind, t, s)
+}
+
+/// Whether the points `test` and `test + 1` lie on the same side of the line through `line` and
+/// `line + 1`.
+fn on_same_side(cubic: &Cubic, test: usize, line: usize) -> bool {
+    let origin = cubic[line];
+    let direction = cubic[line + 1] - origin;
+    let crosses: Vec<Scalar> = (0..2).map(|i| direction.perp(&(cubic[test + i] - origin))).collect();
+    crosses[0] * crosses[1] >= 0.0
+}
+
+/// The trait for analyzing the shape of a cubic: its classification, inflections, loop and cusp.
+pub trait ClassifyCubic {
+    /// Classify the cubic, like Skia's `SkClassifyCubic`.
+    fn classify(&self) -> CubicType;
+    /// Parameters of the inflection points strictly between _t_=0 and _t_=1, ascending.
+    fn inflections(&self) -> Vec<Scalar>;
+    /// If the cubic crosses itself between _t_=0 and _t_=1, the two parameters of the crossing,
+    /// ascending.
+    fn self_intersection(&self) -> Option<[Scalar; 2]>;
+    /// If the cubic has a cusp strictly between _t_=0 and _t_=1, its parameter.
+    fn cusp(&self) -> Option<Scalar>;
+}
+
+impl ClassifyCubic for Cubic {
+    fn classify(&self) -> CubicType {
+        classify_cubic(self).0
+    }
+
+    // translation of SkFindCubicInflections
+    fn inflections(&self) -> Vec<Scalar> {
+        let [p0, p1, p2, p3] = *self;
+        let a = p1 - p0;
+        let b = p2 - p1 * 2.0 + p0;
+        let c = p3 + 3.0 * (p1 - p2) - p0;
+        let mut t_values = [0.0; 2];
+        let roots = find_unit_quad_roots(b.perp(&c), a.perp(&c), a.perp(&b), &mut t_values);
+        t_values[..roots].to_vec()
+    }
+
+    fn self_intersection(&self) -> Option<[Scalar; 2]> {
+        match classify_cubic(self) {
+            (CubicType::Loop, t, s) => {
+                let (t0, t1) = ((t[0] / s[0]) as Scalar, (t[1] / s[1]) as Scalar);
+                let (t0, t1) = (t0.min(t1), t0.max(t1));
+                (t0 >= 0.0 && t1 <= 1.0 && t0 != t1).then_some([t0, t1])
+            }
+            _ => None,
+        }
+    }
+
+    // translation of SkFindCubicCusp
+    fn cusp(&self) -> Option<Scalar> {
+        let [p0, p1, p2, p3] = *self;
+        // When the adjacent control point matches the end point, it behaves as if the cubic has
+        // a cusp: there's a point of max curvature where the derivative goes to zero. Ideally,
+        // this would be where t is zero or one, but math error makes not so. It is not uncommon
+        // to create cubics this way; skip them.
+        if p0 == p1 || p2 == p3 {
+            return None;
+        }
+        // Cubics only have a cusp if the line segments formed by the control and end points
+        // cross. Detect crossing if line ends are on opposite sides of plane formed by the other
+        // line.
+        if on_same_side(self, 0, 2) || on_same_side(self, 2, 0) {
+            return None;
+        }
+        // A cusp is where F′·F″ = 0 (a point of max curvature), and also has a derivative close
+        // to zero. Choose the 'close to zero' meaning by comparing the derivative length with the
+        // overall cubic size.
+        let precision = ((p1 - p0).norm_squared() + (p2 - p1).norm_squared() + (p3 - p2).norm_squared()) * 1e-8;
+        // All three max curvature t values may be close to the cusp; return the first one.
+        self.max_curvature()
+            .into_iter()
+            .filter(|&t| 0.0 < t && t < 1.0)
+            .find(|&t| self.eval_tangent_at(t).norm_squared() < precision)
+    }
+}
@@ -1,11 +1,18 @@
 //! cu2qu.rs — cubic Bézier to quadratic Bézier
 use std::{error::Error, fmt};
 
+use crate::co2qu::{ChopAtExtrema, ChopAtTValues};
 use crate::point;
-use crate::types::{Cubic, Point, Quad, QuadSpline};
+use crate::types::{Cubic, Point, Quad, QuadSpline, Scalar};
 
+mod adaptive;
+mod classify;
+pub use classify::{ClassifyCubic, CubicType};
+
+/// The most quads cu2qu approximates a cubic with. A buffer this long is always long enough for
+/// [`CurveToQuadratic::curve_to_quadratic_into`].
 // We won't divide any cubic over 100 times
-const MAX_N: usize = 100;
+pub const MAX_N: usize = 100;
 
 /// `Coefficients` trait is implemented by types that can be converted to their respective coefficients _and back to points_.
 trait Coefficients {
@@ -51,55 +58,61 @@ impl Error for ApproxNotFoundError {
 }
 
 trait SplitCubic {
-    /// Splits cubic curve into n equal parts
-    fn split_into_n(&self, n: usize) -> Vec<Cubic>;
+    /// Splits cubic curve into n equal parts, one at a time
+    fn split_into_n(&self, n: usize) -> impl Iterator<Item = Cubic>;
 }
 
 /// Cubic Bézier approximation with N quadratic splines
 pub trait CubicApprox {
     /// Calculates the approximate control point of a cubic Bézier curve to a quadratic Bézier.
-    fn approx_control(&self, t: f32) -> Point;
+    fn approx_control(&self, t: Scalar) -> Point;
     /// Returns a quadratic representation with a tolerance error.
-    fn approx_quadratic(&self, tolerance: f32) -> Result<Quad, ApproxNotFoundError>;
+    fn approx_quadratic(&self, tolerance: Scalar) -> Result<Quad, ApproxNotFoundError>;
     /// Returns an array of quadratic spline segments with a tolerance error.
-    fn approx_spline(&self, n: usize, tolerance: f32) -> Result<QuadSpline, ApproxNotFoundError>;
+    fn approx_spline(&self, n: usize, tolerance: Scalar) -> Result<QuadSpline, ApproxNotFoundError>;
+    /// Like [`CubicApprox::approx_spline`], but writes the `n` quads into the start of `quads`
+    /// rather than allocating them. If it fails, some of them may have been written.
+    ///
+    /// Panics if `quads` is shorter than `n`.
+    fn approx_spline_into(&self, n: usize, tolerance: Scalar, quads: &mut [Quad]) -> Result<(), ApproxNotFoundError>;
 }
 
 /// Trait that returns true if the control point of a quadratic Bézier curve stays in the bounding box of the cubic Bézier curve.
+///
+/// `self` is the difference between two cubics; the check passes if no point of it is farther than
+/// `tolerance` from the origin.
 pub trait CubicFarthestFitInside {
-    fn farthest_fit_inside(&self, tolerance: f32) -> bool;
+    fn farthest_fit_inside(&self, tolerance: Scalar) -> bool;
 }
 
 impl SplitCubic for Cubic {
-    fn split_into_n(&self, n: usize) -> Vec<Cubic> {
+    fn split_into_n(&self, n: usize) -> impl Iterator<Item = Cubic> {
         let [a, b, c, d] = (*self).coefficients();
-        let dt = 1f32 / n as f32;
+        let dt = 1.0 / n as Scalar;
         let delta_2 = dt * dt;
         let delta_3 = dt * delta_2;
-        (0..n)
-            .map(|i| {
-                let t1 = i as f32 * dt;
-                let t1_2 = t1 * t1;
-                let a1 = a * delta_3;
-                let b1 = (3f32 * a * t1 + b) * delta_2;
-                let c1 = (2f32 * b * t1 + c + 3f32 * a * t1_2) * dt;
-                let d1 = a * t1 * t1_2 + b * t1_2 + c * t1 + d;
-                [a1, b1, c1, d1].points()
-            })
-            .collect()
+        (0..n).map(move |i| {
+            let t1 = i as Scalar * dt;
+            let t1_2 = t1 * t1;
+            let a1 = a * delta_3;
+            let b1 = (3.0 * a * t1 + b) * delta_2;
+            let c1 = (2.0 * b * t1 + c + 3.0 * a * t1_2) * dt;
+            let d1 = a * t1 * t1_2 + b * t1_2 + c * t1 + d;
+            [a1, b1, c1, d1].points()
+        })
     }
 }
 
 impl CubicApprox for Cubic {
-    fn approx_control(&self, t: f32) -> Point {
+    fn approx_control(&self, t: Scalar) -> Point {
         let [p0, p1, p2, p3] = self;
         let _p1 = *p0 + (*p1 - *p0) * 1.5;
         let _p2 = *p3 + (*p2 - *p3) * 1.5;
         _p1 + (_p2 - _p1) * t
     }
-    fn approx_quadratic(&self, tolerance: f32) -> Result<Quad, ApproxNotFoundError> {
+    fn approx_quadratic(&self, tolerance: Scalar) -> Result<Quad, ApproxNotFoundError> {
         let q1 = calc_intersect(self[0], self[1], self[2], self[3]);
-        if q1[1].is_nan() {
+        if !q1.iter().all(|c| c.is_finite()) {
             return Err(ApproxNotFoundError);
         }
         let c0 = self[0];
@@ -112,16 +125,22 @@ impl CubicApprox for Cubic {
         }
         Ok([c0, q1, c3])
     }
-    fn approx_spline(&self, n: usize, tolerance: f32) -> Result<QuadSpline, ApproxNotFoundError> {
+    fn approx_spline(&self, n: usize, tolerance: Scalar) -> Result<QuadSpline, ApproxNotFoundError> {
+        let mut quads = vec![[Point::default(); 3]; n];
+        self.approx_spline_into(n, tolerance, &mut quads)?;
+        Ok(quads)
+    }
+    fn approx_spline_into(&self, n: usize, tolerance: Scalar, quads: &mut [Quad]) -> Result<(), ApproxNotFoundError> {
+        let quads = &mut quads[..n];
         if n == 1 {
-            return self.approx_quadratic(tolerance).map(|ok| vec![ok]);
+            quads[0] = self.approx_quadratic(tolerance)?;
+            return Ok(());
         }
-        let mut cubics = self.split_into_n(n).into_iter();
+        let mut cubics = self.split_into_n(n);
         let mut next_cubic = cubics.next().unwrap();
-        let mut next_q1 = next_cubic.approx_control(0f32);
+        let mut next_q1 = next_cubic.approx_control(0.0);
         let mut q2 = self[0];
-        let mut d1 = point![0f32, 0f32];
-        let mut spline: Vec<Point> = vec![self[0]];
+        let mut d1 = point![0.0, 0.0];
         for i in 1..(n + 1) {
             let _c0 = next_cubic[0];
             let c1 = next_cubic[1];
@@ -131,66 +150,52 @@ impl CubicApprox for Cubic {
             let q1 = next_q1;
             (next_cubic, next_q1, q2) = if i < n {
                 next_cubic = cubics.next().unwrap();
-                next_q1 = next_cubic.approx_control(i as f32 / (n - 1) as f32);
+                next_q1 = next_cubic.approx_control(i as Scalar / (n - 1) as Scalar);
                 q2 = (q1 + next_q1) * 0.5;
-                spline.push(next_q1);
                 (next_cubic, next_q1, q2)
             } else {
                 (next_cubic, next_q1, c3)
             };
             let d0 = d1;
             d1 = q2 - c3;
-            if d1.norm_squared() > tolerance
+            if d1.norm_squared() > tolerance * tolerance
                 || ![d0, q0 + (q1 - q0) * (2.0 / 3.0) - c1, q2 + (q1 - q2) * (2.0 / 3.0) - c2, d1].farthest_fit_inside(tolerance)
             {
                 return Err(ApproxNotFoundError);
             }
+            // The on-curve points between consecutive quads are implied, halfway between their
+            // off-curve points.
+            quads[i - 1] = [q0, q1, if i == n { self[3] } else { q2 }];
         }
-        spline.push(self[3]);
-        let mut splines = vec![];
-        let mut iter = spline.iter().peekable();
-        let mut prev = iter.next().unwrap();
-        let mut cur_spline = vec![];
-        while let Some(n) = iter.next() {
-            let p_ = iter.peek().map(|p| p.clone());
-            if let Some(p) = p_ {
-                cur_spline.push(prev);
-                cur_spline.push(n);
-                cur_spline.push(p);
-            } else {
-                break;
-            };
-            splines.push(cur_spline);
-            cur_spline = vec![];
-            prev = splines.last().unwrap().last().unwrap();
-        }
-        Ok(splines.into_iter().map(|q| [*q[0], *q[1], *q[2]]).collect())
+        Ok(())
     }
 }
 
-/// Calculate point of intersection between two curves.
+/// Calculate point of intersection between the line through `a` and `b` and the line through `c`
+/// and `d`, such as the tangents at the ends of a cubic.
 ///
-/// Returns a `Point` representing the intersection of two quadratic Bézier curves.
+/// The result is not finite if the lines are parallel.
 fn calc_intersect(a: Point, b: Point, c: Point, d: Point) -> Point {
     let ab = b - a;
     let cd = d - c;
-    let p = ab.component_mul(&point![0f32, 1f32]);
+    let p = point![-ab[1], ab[0]];
     let h = p.dot(&(a - c)) / p.dot(&cd);
     c + cd * h
 }
 
 impl CubicFarthestFitInside for Cubic {
-    fn farthest_fit_inside(&self, tolerance: f32) -> bool {
+    fn farthest_fit_inside(&self, tolerance: Scalar) -> bool {
         let p0 = self[0];
         let p1 = self[1];
         let p2 = self[2];
         let p3 = self[3];
-        if p2.norm_squared() <= tolerance && p1.norm_squared() <= tolerance {
+        let tolerance_squared = tolerance * tolerance;
+        if p2.norm_squared() <= tolerance_squared && p1.norm_squared() <= tolerance_squared {
             return true;
         }
 
-        let mid = (p0 + 3f32 * (p1 + p2) + p3) * 0.125;
-        if mid.norm_squared() > tolerance {
+        let mid = (p0 + 3.0 * (p1 + p2) + p3) * 0.125;
+        if mid.norm_squared() > tolerance_squared {
             return false;
         }
         let deriv3 = (p3 + p2 - p1 - p0) * 0.125;
@@ -200,56 +205,154 @@ impl CubicFarthestFitInside for Cubic {
     }
 }
 
+/// Options controlling how a cubic is split before it is approximated.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SplitOptions {
+    /// Split the cubic at its x and y extrema first, so that every extremum becomes an on-curve
+    /// point of the resulting spline, as TrueType hinting expects.
+    pub at_extrema: bool,
+    /// Split the cubic at its inflections and cusp first, as Skia does before converting cubics to
+    /// quads. S-shaped cubics then need fewer quads, and cusps no longer need to be approximated
+    /// by a single quad's tangents.
+    pub at_inflections: bool,
+    /// Choose the parameters at which the cubic is split into quads by their errors, rather than
+    /// splitting it into equal parts, wherever that needs fewer quads. The on-curve points between
+    /// the quads are still implied.
+    pub adaptive: bool,
+}
+
 /// Convert a cubic Bézier curve to a quadratic spline segment.
+///
+/// `max_err` is a distance in font units, as are all tolerances in this crate: no point of the
+/// spline is farther than it from the cubic. The same number given to
+/// [`Conic::as_quads`](crate::co2qu::Conic::as_quads) gives comparable accuracy.
 pub trait CurveToQuadratic {
-    fn curve_to_quadratic(&self, max_err: f32) -> Result<QuadSpline, ApproxNotFoundError>;
+    fn curve_to_quadratic(&self, max_err: Scalar) -> Result<QuadSpline, ApproxNotFoundError>;
+    /// Like [`CurveToQuadratic::curve_to_quadratic`], but first splitting the cubic as `options`
+    /// asks. Each piece is approximated on its own, and the resulting splines are concatenated.
+    fn curve_to_quadratic_with(&self, max_err: Scalar, options: &SplitOptions) -> Result<QuadSpline, ApproxNotFoundError>;
+    /// Like [`CurveToQuadratic::curve_to_quadratic`], but writes the quads into the start of
+    /// `quads` rather than allocating them, and returns how many there are. Fails if more quads are
+    /// needed than fit.
+    fn curve_to_quadratic_into(&self, max_err: Scalar, quads: &mut [Quad]) -> Result<usize, ApproxNotFoundError>;
 }
 
 impl CurveToQuadratic for Cubic {
-    fn curve_to_quadratic(&self, max_err: f32) -> Result<QuadSpline, ApproxNotFoundError> {
-        for n in 1..(MAX_N + 1) {
-            let spline = self.approx_spline(n, max_err);
-            if let Ok(spl) = spline {
-                return Ok(spl);
+    /// Finds the fewest quads which approximate the cubic, as fontTools does by trying 1, 2, 3, …
+    /// quads in turn, but by [`fewest_quads`]'s search rather than trying them all.
+    fn curve_to_quadratic(&self, max_err: Scalar) -> Result<QuadSpline, ApproxNotFoundError> {
+        let mut quads = vec![[Point::default(); 3]; MAX_N];
+        let n = self.curve_to_quadratic_into(max_err, &mut quads)?;
+        quads.truncate(n);
+        Ok(quads)
+    }
+
+    fn curve_to_quadratic_into(&self, max_err: Scalar, quads: &mut [Quad]) -> Result<usize, ApproxNotFoundError> {
+        let max_n = quads.len().min(MAX_N);
+        // The number of quads last tried, which `quads` holds if they were found.
+        let mut tried = 0;
+        let (found, ()) = fewest_quads(min_quads(self, max_err), max_n, |n| {
+            tried = n;
+            self.approx_spline_into(n, max_err, quads).ok()
+        })
+        .ok_or(ApproxNotFoundError)?;
+        if tried != found {
+            self.approx_spline_into(found, max_err, quads)?;
+        }
+        Ok(found)
+    }
+
+    fn curve_to_quadratic_with(&self, max_err: Scalar, options: &SplitOptions) -> Result<QuadSpline, ApproxNotFoundError> {
+        let mut t_values = vec![];
+        if options.at_inflections {
+            t_values.extend(self.inflections());
+            t_values.extend(self.cusp());
+            t_values.sort_by(|a, b| a.partial_cmp(b).expect("Inflections are never NaN"));
+            // A cusp is also found as a (double) inflection.
+            t_values.dedup_by(|b, a| *b - *a < Scalar::EPSILON * 10.);
+        }
+        let mut pieces = self.chop_at_t_values(&t_values);
+        if options.at_extrema {
+            pieces = pieces.iter().flat_map(|piece| piece.make_monotonic()).collect();
+        }
+        let mut spline = QuadSpline::new();
+        for piece in pieces {
+            if options.adaptive {
+                spline.extend(adaptive::curve_to_quadratic(&piece, max_err)?);
+            } else {
+                spline.extend(piece.curve_to_quadratic(max_err)?);
             }
         }
-        Err(ApproxNotFoundError)
+        Ok(spline)
+    }
+}
+
+/// A lower bound on the number of quads approximating the cubic within `max_err`, between 1 and
+/// `MAX_N`.
+///
+/// A quad can't follow the cubic term _a_·_t_³ of the cubic. Over each of _n_ equal parts it is
+/// _a_/_n_³·_s_³, and the nearest a quadratic polynomial comes to that over 0 ≤ _s_ ≤ 1 is |_a_|/32_n_³
+/// (by Chebyshev's equioscillation theorem), so fewer than ∛(|_a_|/32`max_err`) quads always fail.
+fn min_quads(cubic: &Cubic, max_err: Scalar) -> usize {
+    let [a, _, _, _] = cubic.coefficients();
+    // Leave a margin for rounding error.
+    let bound = (a.norm() / (32.0 * max_err)).cbrt() * 0.99;
+    if bound.is_nan() {
+        return 1;
+    }
+    (bound.floor() as usize).clamp(1, MAX_N)
+}
+
+/// The fewest quads from `lower` to `max_n` for which `approximate` succeeds, and its approximation.
+/// Rather than trying each number in turn, it gallops upwards from `lower` until an approximation
+/// is found, and then bisects between the last failure and it.
+///
+/// Every number it returns has been checked: the number below it has been tried and failed, or
+/// else is below `lower`, which must be a lower bound such as [`min_quads`]. The result is the
+/// fewest if, as in practice, approximation fails for every number of quads below the fewest which
+/// succeed. An approximation by fewer quads, after a failure by more, would be missed.
+fn fewest_quads<T>(lower: usize, max_n: usize, mut approximate: impl FnMut(usize) -> Option<T>) -> Option<(usize, T)> {
+    // `failed` has been tried and failed, or is below `lower`.
+    let mut failed = lower - 1;
+    if failed >= max_n {
+        return None;
+    }
+    let mut step = 1;
+    let mut found = loop {
+        let n = (failed + step).min(max_n);
+        match approximate(n) {
+            Some(approximation) => break (n, approximation),
+            None if n == max_n => return None,
+            None => failed = n,
+        }
+        step *= 2;
+    };
+    // The gap between `failed` and `found` is halved until it is 1, so `found - 1` is `failed`.
+    while found.0 - failed > 1 {
+        let n = (failed + found.0) / 2;
+        match approximate(n) {
+            Some(approximation) => found = (n, approximation),
+            None => failed = n,
+        }
     }
+    Some(found)
 }
 
 /// Convert a vector of cubic Bézier curves to a vector of quadratic spline segments.
 pub trait CurvesToQuadratic {
-    fn curves_to_quadratic(&self, max_errors: Vec<f32>) -> Result<Vec<QuadSpline>, ApproxNotFoundError>;
+    fn curves_to_quadratic(&self, max_errors: Vec<Scalar>) -> Result<Vec<QuadSpline>, ApproxNotFoundError>;
 }
 
 impl CurvesToQuadratic for Vec<Cubic> {
-    fn curves_to_quadratic(&self, max_errors: Vec<f32>) -> Result<Vec<QuadSpline>, ApproxNotFoundError> {
+    /// Every spline has the same number of quads, the fewest which approximate every cubic, found
+    /// by [`fewest_quads`]'s search.
+    fn curves_to_quadratic(&self, max_errors: Vec<Scalar>) -> Result<Vec<QuadSpline>, ApproxNotFoundError> {
         debug_assert_eq!(self.len(), max_errors.len());
-        let l = self.len();
-        let mut splines = vec![None; l];
-        let mut last_i = 0;
-        let mut i = 0;
-        let mut n = 1;
-        loop {
-            let test_spline = self[i].approx_spline(n, max_errors[i]);
-            if let Err(_) = test_spline {
-                if n == MAX_N {
-                    break;
-                };
-                n += 1;
-                last_i = i;
-                continue;
-            }
-            splines[i] = match test_spline {
-                Ok(a) => Some(a),
-                Err(e) => return Err(e),
-            };
-            i = (i + 1) % l;
-            if i == last_i {
-                //done
-                return Ok(splines.into_iter().map(|maybe| maybe.expect("Should not get a None")).collect());
-            }
-        }
-        Err(ApproxNotFoundError)
+        let lower = self.iter().zip(&max_errors).map(|(cubic, &max_err)| min_quads(cubic, max_err)).max().unwrap_or(1);
+        let (_, splines) = fewest_quads(lower, MAX_N, |n| {
+            self.iter().zip(&max_errors).map(|(cubic, &max_err)| cubic.approx_spline(n, max_err).ok()).collect()
+        })
+        .ok_or(ApproxNotFoundError)?;
+        Ok(splines)
     }
 }
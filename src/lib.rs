@@ -9,6 +9,8 @@ pub use p3d::{Point3, Point3Like};
 mod types;
 pub use types::*;
 
+#[cfg(feature = "rayon")]
+pub mod batch;
 pub mod co2qu;
 pub mod cu2qu;
 pub mod qu2cu;
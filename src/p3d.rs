@@ -3,27 +3,30 @@ use glifparser::{
     PointData, PointLike,
 };
 use nalgebra::{vector, ArrayStorage, Const, Matrix};
+use num_traits::AsPrimitive;
+
+use crate::Scalar;
 
 /// A 3D point type, containing X, Y, and Z coordinates.
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Point3 {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
+    pub x: Scalar,
+    pub y: Scalar,
+    pub z: Scalar,
 }
 
 impl PointLike for Point3 {
     fn x(&self) -> IntegerOrFloat {
-        Float(self.x)
+        Float(self.x.as_())
     }
     fn y(&self) -> IntegerOrFloat {
-        Float(self.y)
+        Float(self.y.as_())
     }
     fn set_x(&mut self, x: IntegerOrFloat) {
-        self.x = x.into();
+        self.x = f32::from(x).as_();
     }
     fn set_y(&mut self, y: IntegerOrFloat) {
-        self.y = y.into();
+        self.y = f32::from(y).as_();
     }
 }
 
@@ -35,18 +38,18 @@ pub trait Point3Like: PointLike {
 
 impl Point3Like for Point3 {
     fn z(&self) -> IntegerOrFloat {
-        Float(self.z)
+        Float(self.z.as_())
     }
     fn set_z(&mut self, z: IntegerOrFloat) {
-        self.z = z.into();
+        self.z = f32::from(z).as_();
     }
 }
 
-type Point3Matrix = Matrix<f32, Const<3_usize>, Const<1_usize>, ArrayStorage<f32, 3_usize, 1_usize>>;
+type Point3Matrix = Matrix<Scalar, Const<3_usize>, Const<1_usize>, ArrayStorage<Scalar, 3_usize, 1_usize>>;
 
 impl Point3 {
     /// Construct a new point.
-    pub fn new(x: impl Into<f32>, y: impl Into<f32>, z: impl Into<f32>) -> Self {
+    pub fn new(x: impl Into<Scalar>, y: impl Into<Scalar>, z: impl Into<Scalar>) -> Self {
         Self {
             x: x.into(),
             y: y.into(),
@@ -55,7 +58,7 @@ impl Point3 {
     }
     /// Convert the Point3 to a matrix suitable for linear algebra.
     pub(crate) fn as_linalg(&self) -> Point3Matrix {
-        vector![self.x().into(), self.y().into(), self.z().into()]
+        vector![self.x, self.y, self.z]
     }
     /// Construct a Point3 from a matrix suitable for linear algebra.
     pub(crate) fn from_linalg(f: Point3Matrix) -> Self {
@@ -3,6 +3,7 @@ use glifparser::{
     IntegerOrFloat::{self, Float},
     PointData, PointLike,
 };
+use num_traits::AsPrimitive;
 
 /// Convenience point constructor.
 #[macro_export]
@@ -29,8 +30,27 @@ pub type CubicSpline = Vec<Cubic>;
 /// [quad-spline]: https://en.wikipedia.org/wiki/B-spline#Quadratic_splines
 pub type QuadSpline = Vec<Quad>;
 
-pub(crate) type Float2 =
-    nalgebra::Matrix<f32, nalgebra::Const<2_usize>, nalgebra::Const<1_usize>, nalgebra::ArrayStorage<f32, 2_usize, 1_usize>>;
+/// The type of coordinates, weights and parameters throughout the crate: `f32`, or `f64` with the
+/// `f64` feature, for coordinates too large for `f32` to chop and intersect precisely.
+///
+/// Converting to and from [`glifparser`]'s points goes through `f32` either way.
+#[cfg(not(feature = "f64"))]
+pub type Scalar = f32;
+#[cfg(feature = "f64")]
+pub type Scalar = f64;
+
+/// Mathematical constants of the precision of [`Scalar`].
+#[cfg(not(feature = "f64"))]
+pub use std::f32::consts as scalar_consts;
+#[cfg(feature = "f64")]
+pub use std::f64::consts as scalar_consts;
+
+pub(crate) type Float2 = nalgebra::Matrix<
+    Scalar,
+    nalgebra::Const<2_usize>,
+    nalgebra::Const<1_usize>,
+    nalgebra::ArrayStorage<Scalar, 2_usize, 1_usize>,
+>;
 
 /// A two-dimensional point (with x and y coordinates).
 #[derive(
@@ -53,8 +73,8 @@ pub struct Point(pub Float2);
 impl PointData for Point {}
 
 impl Point {
-    pub const fn from_xy(x: f32, y: f32) -> Self {
-        Point(nalgebra::SMatrix::from_array_storage(nalgebra::ArrayStorage::<f32, 2, 1>([[x, y]])))
+    pub const fn from_xy(x: Scalar, y: Scalar) -> Self {
+        Point(nalgebra::SMatrix::from_array_storage(nalgebra::ArrayStorage::<Scalar, 2, 1>([[x, y]])))
     }
 }
 
@@ -63,29 +83,81 @@ impl Point {
 /// This allows us to use the `Point` structure in a `glifparser`-compatible way.
 impl PointLike for Point {
     fn x(&self) -> IntegerOrFloat {
-        Float(self.0[0])
+        Float(self.0[0].as_())
     }
     fn y(&self) -> IntegerOrFloat {
-        Float(self.0[1])
+        Float(self.0[1].as_())
     }
     fn set_x(&mut self, x: IntegerOrFloat) {
-        self.0[0] = x.into();
+        self.0[0] = f32::from(x).as_();
     }
     fn set_y(&mut self, y: IntegerOrFloat) {
-        self.0[1] = y.into();
+        self.0[1] = f32::from(y).as_();
     }
 }
 
 /// The trait for types which implement the calculation of their [derivative coefficients][der-coeffs].
 ///
+/// Coefficients are returned highest power first, with the x and y axes in the respective lanes of
+/// each `Point`.
+///
 /// [der-coeffs]: https://wikipedia.org/wiki/B%C3%A9zier_curve#Higher-order_B%C3%A9zier_curves
 pub trait DerivativeCoefficients<const N: usize> {
-    fn deriv_coeff(&self) -> [f32; N];
+    fn deriv_coeff(&self) -> [Point; N];
+}
+
+/// An axis-aligned rectangle, such as the bounding box of a segment.
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct Rect {
+    /// The corner with the smallest x and y coordinates.
+    pub min: Point,
+    /// The corner with the largest x and y coordinates.
+    pub max: Point,
+}
+
+impl Rect {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    /// The smallest rectangle containing all `points`. An empty iterator yields the default
+    /// (zero-sized) rectangle at the origin.
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Self {
+        let mut points = points.into_iter();
+        let first = match points.next() {
+            Some(p) => p,
+            None => return Self::default(),
+        };
+        points.fold(Self::new(first, first), |rect, p| Self::new(Point(rect.min.inf(&p)), Point(rect.max.sup(&p))))
+    }
+
+    /// The smallest rectangle containing both `self` and `other`.
+    pub fn union(&self, other: &Rect) -> Self {
+        Self::new(Point(self.min.inf(&other.min)), Point(self.max.sup(&other.max)))
+    }
+
+    pub fn width(&self) -> Scalar {
+        self.max[0] - self.min[0]
+    }
+
+    pub fn height(&self) -> Scalar {
+        self.max[1] - self.min[1]
+    }
+
+    /// The length of the rectangle's diagonal.
+    pub fn diagonal(&self) -> Scalar {
+        self.width().hypot(self.height())
+    }
+
+    /// Whether `p` lies inside the rectangle or on its edge.
+    pub fn contains(&self, p: Point) -> bool {
+        (self.min[0]..=self.max[0]).contains(&p[0]) && (self.min[1]..=self.max[1]).contains(&p[1])
+    }
 }
 
 use std::ops::{Add, Mul};
 
-impl Mul<Point> for f32 {
+impl Mul<Point> for Scalar {
     type Output = Point;
     fn mul(self, rhs: Point) -> Point {
         Point(self * rhs.0)
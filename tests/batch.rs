@@ -0,0 +1,84 @@
+#![cfg(feature = "rayon")]
+
+use cucoqu::batch::{BatchStats, ParToQuads};
+use cucoqu::co2qu::{Conic, ToQuads, Tolerance};
+use cucoqu::point;
+use cucoqu::{Cubic, CubicSpline, Point, Scalar};
+
+use cucoqu::scalar_consts::FRAC_1_SQRT_2;
+
+fn contour(i: usize) -> CubicSpline {
+    let x = i as Scalar * 10.0;
+    vec![
+        [point![x, 0.0], point![x, 200.0], point![x + 300.0, 400.0], point![x + 500.0, 100.0]],
+        [point![x + 500.0, 100.0], point![x + 600.0, 0.0], point![x + 100.0, -300.0], point![x, 0.0]],
+    ]
+}
+
+#[test]
+fn contours_in_order() {
+    let contours: Vec<CubicSpline> = (0..64).map(contour).collect();
+    let tolerance = Tolerance::Absolute(0.5);
+    let batch = contours.par_to_quads(tolerance);
+    assert_eq!(batch.results.len(), contours.len());
+    let mut quads = 0;
+    for (contour, result) in contours.iter().zip(&batch.results) {
+        let expected = contour.to_quads(tolerance).unwrap();
+        quads += expected.len();
+        assert_eq!(result.as_ref().unwrap(), &expected);
+    }
+    assert_eq!(
+        batch.stats,
+        BatchStats {
+            contours: 64,
+            segments: 128,
+            quads,
+            failures: 0,
+        }
+    );
+}
+
+#[test]
+fn failures_are_counted() {
+    // A cubic this big can't be approximated within a unit by MAX_N quads, but the other contours
+    // still are.
+    let huge: Cubic = contour(0)[0].map(|p| p * 1e7);
+    let contours = [contour(0), vec![huge], contour(1)];
+    let batch = contours.par_to_quads(Tolerance::Absolute(1.0));
+    assert!(batch.results[0].is_ok());
+    assert!(batch.results[1].is_err());
+    assert!(batch.results[2].is_ok());
+    assert_eq!(batch.stats.contours, 3);
+    assert_eq!(batch.stats.segments, 5);
+    assert_eq!(batch.stats.failures, 1);
+}
+
+#[test]
+fn glyphs_of_conics() {
+    let quarter = |r: Scalar| {
+        vec![
+            Conic::new(point![r, 0.0], point![r, r], point![0.0, r], FRAC_1_SQRT_2),
+            Conic::new(point![0.0, r], point![-r, r], point![-r, 0.0], FRAC_1_SQRT_2),
+        ]
+    };
+    let glyphs: Vec<Vec<Vec<Conic>>> = vec![vec![quarter(100.0), quarter(500.0)], vec![], vec![quarter(50.0)]];
+    let batch = glyphs.par_to_quads(Tolerance::Relative(0.001));
+    assert_eq!(batch.results.iter().map(Vec::len).collect::<Vec<_>>(), [2, 0, 1]);
+    assert_eq!(batch.results[0][1].as_ref().unwrap(), &quarter(500.0).to_quads(Tolerance::Relative(0.001)).unwrap());
+    assert_eq!(batch.stats.contours, 3);
+    assert_eq!(batch.stats.segments, 6);
+    let quads: usize = batch.results.iter().flatten().map(|result| result.as_ref().unwrap().len()).sum();
+    assert_eq!(batch.stats.quads, quads);
+}
+
+#[test]
+fn same_output_on_one_thread() {
+    let contours: Vec<CubicSpline> = (0..32).map(contour).collect();
+    let parallel = contours.par_to_quads(Tolerance::Absolute(0.25));
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let serial = pool.install(|| contours.par_to_quads(Tolerance::Absolute(0.25)));
+    assert_eq!(parallel.stats, serial.stats);
+    for (a, b) in parallel.results.iter().zip(&serial.results) {
+        assert_eq!(a.as_ref().unwrap(), b.as_ref().unwrap());
+    }
+}
@@ -0,0 +1,87 @@
+use cucoqu::co2qu::{Bounds, Conic, EvalAt, Extrema};
+use cucoqu::point;
+use cucoqu::{Cubic, Point, Quad, Rect, Scalar};
+
+mod common;
+use common::assert_close;
+
+/// The tight bounds must contain every point on the curve and be touched by the curve on all sides.
+fn assert_tight<S: Bounds + EvalAt>(segment: &S) {
+    let tight = segment.tight_bounds();
+    let samples = Rect::from_points((0..=1000).map(|i| segment.eval_at(i as Scalar / 1000.0)));
+    assert_close(tight.min[0], samples.min[0], 1e-3);
+    assert_close(tight.min[1], samples.min[1], 1e-3);
+    assert_close(tight.max[0], samples.max[0], 1e-3);
+    assert_close(tight.max[1], samples.max[1], 1e-3);
+}
+
+#[test]
+fn cubic_extrema() {
+    let arch: Cubic = [point![0.0, 0.0], point![0.0, 100.0], point![100.0, 100.0], point![100.0, 0.0]];
+    assert!(arch.x_extrema().is_empty());
+    assert_eq!(arch.y_extrema(), vec![0.5]);
+
+    let s_curve: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![70.0, -100.0], point![100.0, 0.0]];
+    let ys = s_curve.y_extrema();
+    assert_eq!(ys.len(), 2);
+    assert!(ys[0] < ys[1]);
+    for t in ys {
+        let [a, b, c] = [s_curve[0][1], s_curve[1][1], s_curve[2][1]];
+        let d = s_curve[3][1];
+        let mt = 1.0 - t;
+        let dy = 3.0 * mt * mt * (b - a) + 6.0 * mt * t * (c - b) + 3.0 * t * t * (d - c);
+        assert!(dy.abs() < 1e-2, "y' = {} at {}", dy, t);
+    }
+}
+
+#[test]
+fn cubic_bounds() {
+    let arch: Cubic = [point![0.0, 0.0], point![0.0, 100.0], point![100.0, 100.0], point![100.0, 0.0]];
+    assert_eq!(arch.fast_bounds(), Rect::new(point![0.0, 0.0], point![100.0, 100.0]));
+    let tight = arch.tight_bounds();
+    assert_close(tight.max[1], 75.0, 1e-3);
+    assert_tight(&arch);
+
+    let loopy: Cubic = [point![0.0, 0.0], point![-50.0, 50.0], point![150.0, 50.0], point![100.0, 0.0]];
+    assert_eq!(loopy.x_extrema().len(), 2);
+    assert_tight(&loopy);
+}
+
+#[test]
+fn quad_bounds() {
+    let quad: Quad = [point![0.0, 0.0], point![50.0, 100.0], point![100.0, 0.0]];
+    assert!(quad.x_extrema().is_empty());
+    assert_eq!(quad.y_extrema(), vec![0.5]);
+    assert_close(quad.tight_bounds().max[1], 50.0, 1e-3);
+    assert_tight(&quad);
+    assert_tight(&[point![0.0, 0.0], point![120.0, 40.0], point![10.0, 80.0]] as &Quad);
+}
+
+#[test]
+fn conic_bounds() {
+    let conic = Conic::new(point![0.0, 0.0], point![50.0, 100.0], point![100.0, 0.0], 0.5);
+    assert!(conic.x_extrema().is_empty());
+    assert_eq!(conic.y_extrema().len(), 1);
+    assert_close(conic.y_extrema()[0], 0.5, 1e-3);
+    assert_eq!(conic.fast_bounds().max[1], 100.0);
+    assert_close(conic.tight_bounds().max[1], 100.0 / 3.0, 1e-3);
+    assert_tight(&conic);
+
+    for weight in [0.2, Scalar::sqrt(2.0) / 2.0, 1.0, 3.0] {
+        assert_tight(&Conic::new(point![10.0, 20.0], point![80.0, -30.0], point![-20.0, 60.0], weight));
+    }
+}
+
+#[test]
+fn spline_bounds() {
+    let spline: Vec<Quad> = vec![
+        [point![0.0, 0.0], point![50.0, 100.0], point![100.0, 0.0]],
+        [point![100.0, 0.0], point![150.0, -100.0], point![200.0, 0.0]],
+    ];
+    let tight = spline.tight_bounds();
+    assert_eq!(tight, Rect::new(point![0.0, -50.0], point![200.0, 50.0]));
+    assert_eq!(spline.fast_bounds(), Rect::new(point![0.0, -100.0], point![200.0, 100.0]));
+    assert!(tight.contains(point![100.0, 0.0]));
+    assert!(!tight.contains(Point::from_xy(100.0, 60.0)));
+    assert_eq!(Vec::<Quad>::new().tight_bounds(), Rect::default());
+}
@@ -0,0 +1,89 @@
+use cucoqu::co2qu::{max_distance, Conic, QuadBudget};
+use cucoqu::cu2qu::{CurveToQuadratic, MAX_N};
+use cucoqu::point;
+use cucoqu::{Cubic, CubicSpline, Point, Scalar};
+
+use cucoqu::scalar_consts::FRAC_1_SQRT_2;
+
+mod common;
+use common::{random_cubic, Rng};
+
+fn s_curve() -> Cubic {
+    [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]]
+}
+
+#[test]
+fn cubic_within_budget() {
+    let cubic = s_curve();
+    let mut last = Scalar::INFINITY;
+    for budget in [2, 4, 8] {
+        let (quads, distance) = cubic.quads_within_budget(budget).unwrap();
+        assert!(quads.len() <= budget);
+        assert_eq!(distance, max_distance(&cubic, &quads).distance);
+        assert!(distance < last, "{} {}", distance, last);
+        last = distance;
+    }
+}
+
+#[test]
+fn as_near_as_a_tolerance_allows() {
+    let cubic = s_curve();
+    let spline = cubic.curve_to_quadratic(0.5).unwrap();
+    let (quads, distance) = cubic.quads_within_budget(spline.len()).unwrap();
+    assert!(quads.len() <= spline.len());
+    assert!(distance <= 0.5, "{}", distance);
+}
+
+#[test]
+#[ignore = "measures every spline of up to MAX_N quads, which takes minutes in a debug build"]
+fn huge_budget() {
+    // The search ends when cu2qu would give up, or halving conics would, however large the budget.
+    let (quads, distance) = s_curve().quads_within_budget(usize::MAX).unwrap();
+    assert!(quads.len() <= MAX_N && distance < 0.01, "{} {}", quads.len(), distance);
+    let conic = Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], FRAC_1_SQRT_2);
+    assert!(conic.quads_within_budget(usize::MAX).is_ok());
+}
+
+#[test]
+fn budget_goes_to_the_farthest_segment() {
+    let contour: CubicSpline = vec![
+        s_curve(),
+        // Exactly a quad.
+        [point![100.0, 0.0], point![200.0 / 3.0, -10.0], point![100.0 / 3.0, -10.0], point![0.0, 0.0]],
+    ];
+    let (quads, distance) = contour.quads_within_budget(6).unwrap();
+    // The rest of the budget goes to the S-curve.
+    let (s_quads, s_distance) = s_curve().quads_within_budget(5).unwrap();
+    assert_eq!(quads.len(), s_quads.len() + 1);
+    assert_eq!(quads[..s_quads.len()], s_quads[..]);
+    assert_eq!(distance, s_distance);
+}
+
+#[test]
+fn conic_within_budget() {
+    let conic = Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], FRAC_1_SQRT_2);
+    // Conics are halved evenly, so only a power of two of quads fits.
+    let (quads, distance) = conic.quads_within_budget(7).unwrap();
+    assert_eq!(quads.len(), 4);
+    assert!(distance < conic.quads_within_budget(2).unwrap().1);
+}
+
+#[test]
+fn budget_too_small() {
+    let contour: CubicSpline = vec![s_curve(), s_curve()];
+    assert!(contour.quads_within_budget(1).is_err());
+    assert!(CubicSpline::new().quads_within_budget(10).is_err());
+}
+
+#[test]
+fn random_contours_as_near_as_a_tolerance_allows() {
+    let mut rng = Rng::default();
+    for _ in 0..4 {
+        let contour: CubicSpline = (0..2).map(|_| random_cubic(&mut rng)).collect();
+        // cu2qu's quads for each segment fit in the budget, so sharing it out does at least as well.
+        let budget: usize = contour.iter().map(|cubic| cubic.curve_to_quadratic(2.0).unwrap().len()).sum();
+        let (quads, distance) = contour.quads_within_budget(budget).unwrap();
+        assert!(quads.len() <= budget);
+        assert!(distance <= 2.0, "{} for {:?}", distance, contour);
+    }
+}
@@ -0,0 +1,72 @@
+use cucoqu::co2qu::EvalAt;
+use cucoqu::cu2qu::{ClassifyCubic, CubicType};
+use cucoqu::point;
+use cucoqu::{Cubic, Point, Scalar};
+
+#[test]
+fn serpentine() {
+    let s_curve: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]];
+    assert_eq!(s_curve.classify(), CubicType::Serpentine);
+    let inflections = s_curve.inflections();
+    assert_eq!(inflections.len(), 1);
+    let t = inflections[0];
+    // The curvature changes sign at an inflection.
+    let d = |t: Scalar| {
+        let mt = 1.0 - t;
+        let p = s_curve;
+        let d1 = (p[1] - p[0]) * (3.0 * mt * mt) + (p[2] - p[1]) * (6.0 * mt * t) + (p[3] - p[2]) * (3.0 * t * t);
+        let d2 = (p[2] - p[1] * 2.0 + p[0]) * (6.0 * mt) + (p[3] - p[2] * 2.0 + p[1]) * (6.0 * t);
+        d1.perp(&d2)
+    };
+    assert!(d(t - 0.01) * d(t + 0.01) < 0.0);
+    assert_eq!(s_curve.self_intersection(), None);
+    assert_eq!(s_curve.cusp(), None);
+
+    // Point symmetric, so the second inflection is at infinity.
+    let symmetric: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![70.0, -100.0], point![100.0, 0.0]];
+    assert_eq!(symmetric.classify(), CubicType::CuspAtInfinity);
+    assert_eq!(symmetric.inflections(), vec![0.5]);
+}
+
+#[test]
+fn arch_has_no_inflections() {
+    let arch: Cubic = [point![0.0, 0.0], point![0.0, 100.0], point![100.0, 100.0], point![100.0, 0.0]];
+    assert!(arch.inflections().is_empty());
+    assert_eq!(arch.self_intersection(), None);
+    assert_eq!(arch.cusp(), None);
+}
+
+#[test]
+fn loop_self_intersection() {
+    let loopy: Cubic = [point![0.0, 0.0], point![150.0, 100.0], point![-50.0, 100.0], point![100.0, 0.0]];
+    assert_eq!(loopy.classify(), CubicType::Loop);
+    assert!(loopy.inflections().is_empty());
+    let [t0, t1] = loopy.self_intersection().expect("loop should cross itself");
+    assert!(t0 < t1);
+    assert!((loopy.eval_at(t0) - loopy.eval_at(t1)).norm() < 1e-2);
+    assert_eq!(loopy.cusp(), None);
+
+    // The same kind of curve, but the crossing lies beyond the end points.
+    let open: Cubic = [point![0.0, 0.0], point![40.0, 100.0], point![60.0, 100.0], point![100.0, 0.0]];
+    assert_eq!(open.self_intersection(), None);
+}
+
+#[test]
+fn cusp() {
+    // P′(½) = 0 when P2 = P1 + P0 − P3.
+    let cusp: Cubic = [point![0.0, 0.0], point![150.0, 100.0], point![50.0, 100.0], point![100.0, 0.0]];
+    assert_eq!(cusp.classify(), CubicType::LocalCusp);
+    let t = cusp.cusp().expect("cubic should have a cusp");
+    assert!((t - 0.5).abs() < 1e-3);
+    assert_eq!(cusp.self_intersection(), None);
+}
+
+#[test]
+fn degenerate() {
+    // Degree-elevated from the quadratic (0, 0), (30, 60), (90, 0).
+    let quadratic: Cubic = [point![0.0, 0.0], point![20.0, 40.0], point![50.0, 40.0], point![90.0, 0.0]];
+    assert_eq!(quadratic.classify(), CubicType::Quadratic);
+    let line: Cubic = [point![0.0, 0.0], point![10.0, 10.0], point![60.0, 60.0], Point::from_xy(100.0, 100.0)];
+    assert_eq!(line.classify(), CubicType::LineOrPoint);
+    assert!(line.inflections().is_empty());
+}
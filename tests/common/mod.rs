@@ -0,0 +1,46 @@
+//! Helpers shared by the integration tests. Not every test uses every helper.
+#![allow(dead_code)]
+
+use cucoqu::point;
+use cucoqu::{Cubic, Point, Scalar};
+
+pub fn assert_close(a: Scalar, b: Scalar, epsilon: Scalar) {
+    assert!((a - b).abs() < epsilon, "{} != {}", a, b);
+}
+
+/// A xorshift generator, so that tests on random curves are the same every run.
+pub struct Rng(u32);
+
+impl Rng {
+    /// The `seed` must not be zero.
+    pub fn new(seed: u32) -> Self {
+        Self(seed)
+    }
+
+    /// A number from 0 to 999.9 in steps of 0.1, as a coordinate on a 1000-unit em.
+    pub fn scalar(&mut self) -> Scalar {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        (self.0 % 10000) as Scalar / 10.0
+    }
+
+    pub fn point(&mut self) -> Point {
+        point![self.scalar(), self.scalar()]
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new(0x2545_f491)
+    }
+}
+
+/// A cubic anywhere on a 1000-unit em.
+pub fn random_cubic(rng: &mut Rng) -> Cubic {
+    [(); 4].map(|_| rng.point())
+}
+
+pub fn random_points(rng: &mut Rng, count: usize) -> Vec<Point> {
+    (0..count).map(|_| rng.point()).collect()
+}
@@ -0,0 +1,82 @@
+use cucoqu::co2qu::{Chop, Conic};
+use cucoqu::point;
+use cucoqu::Point;
+use cucoqu::Scalar;
+
+const SUBRANGES: &[(Scalar, Scalar)] = &[(0.0, 0.3), (0.1, 0.4), (0.25, 0.75), (0.6, 0.9), (0.7, 1.0)];
+
+/// Evaluate the conic directly from its rational form, independently of the code under test.
+fn eval(conic: &Conic, t: Scalar) -> Point {
+    let mt = 1.0 - t;
+    let (b0, b1, b2) = (mt * mt, 2.0 * mt * t * conic.weight, t * t);
+    (conic.start * b0 + conic.control * b1 + conic.end * b2) / (b0 + b1 + b2)
+}
+
+fn quarter_circle() -> Conic {
+    Conic::new(point![0.0, 0.0], point![0.0, 50.0], point![50.0, 50.0], Scalar::sqrt(2.0) / 2.0)
+}
+
+fn hyperbola() -> Conic {
+    Conic::new(point![10.0, 20.0], point![80.0, -30.0], point![120.0, 60.0], 2.0)
+}
+
+/// Smallest distance from `p` to a dense polyline through `conic` over `t1..t2`.
+fn distance_to(conic: &Conic, t1: Scalar, t2: Scalar, p: Point) -> Scalar {
+    const STEPS: usize = 2000;
+    (0..STEPS)
+        .map(|i| {
+            let a = eval(conic, t1 + (t2 - t1) * i as Scalar / STEPS as Scalar);
+            let b = eval(conic, t1 + (t2 - t1) * (i + 1) as Scalar / STEPS as Scalar);
+            let ab = b - a;
+            let u = ((p - a).dot(&ab) / ab.norm_squared()).clamp(0.0, 1.0);
+            (p - (a + ab * u)).norm()
+        })
+        .fold(Scalar::INFINITY, Scalar::min)
+}
+
+#[test]
+fn chop_at_t2_endpoints() {
+    for conic in [quarter_circle(), hyperbola()] {
+        for &(t1, t2) in SUBRANGES {
+            let sub = conic.chop_at_t2(t1, t2);
+            assert!((sub.start - eval(&conic, t1)).norm() < 1e-3, "start of {}…{}", t1, t2);
+            assert!((sub.end - eval(&conic, t2)).norm() < 1e-3, "end of {}…{}", t1, t2);
+            assert!(sub.weight.is_finite() && sub.weight > 0.0);
+        }
+    }
+}
+
+#[test]
+fn chop_at_t2_stays_on_circle() {
+    let conic = quarter_circle();
+    let center = point![50.0, 0.0];
+    for &(t1, t2) in SUBRANGES {
+        let sub = conic.chop_at_t2(t1, t2);
+        for i in 0..=16 {
+            let p = eval(&sub, i as Scalar / 16.0);
+            assert!(((p - center).norm() - 50.0).abs() < 1e-3, "{:?} off circle for {}…{}", p, t1, t2);
+        }
+    }
+}
+
+#[test]
+fn chop_at_t2_matches_original() {
+    let conic = hyperbola();
+    for &(t1, t2) in SUBRANGES {
+        let sub = conic.chop_at_t2(t1, t2);
+        for i in 0..=16 {
+            let p = eval(&sub, i as Scalar / 16.0);
+            assert!(distance_to(&conic, t1, t2, p) < 1e-2, "{:?} not on original for {}…{}", p, t1, t2);
+        }
+    }
+}
+
+#[test]
+fn chop_halves_in_order() {
+    let conic = hyperbola();
+    let [first, second] = conic.chop();
+    assert_eq!(first.start, conic.start);
+    assert_eq!(first.end, second.start);
+    assert_eq!(second.end, conic.end);
+    assert!((first.end - eval(&conic, 0.5)).norm() < 1e-3);
+}
@@ -0,0 +1,134 @@
+use cucoqu::co2qu::{Conic, Continuity, Smooth};
+use cucoqu::cu2qu::CurveToQuadratic;
+use cucoqu::point;
+use cucoqu::{Cubic, CubicSpline, Point, QuadSpline};
+
+use cucoqu::scalar_consts::{FRAC_1_SQRT_2, FRAC_PI_2};
+
+#[test]
+fn cu2qu_output_is_smooth() {
+    let cubic: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]];
+    let spline = cubic.curve_to_quadratic(0.01).unwrap();
+    assert!(spline.len() > 1);
+    let joints = spline.joints(false);
+    assert_eq!(joints.len(), spline.len() - 1);
+    for (i, joint) in joints.iter().enumerate() {
+        assert_eq!(joint.index, i);
+        assert_eq!(joint.gap, 0.0);
+        assert!(joint.is_g1(0.0, 1e-3), "{:?}", joint);
+        // Quadratic splines are not curvature continuous.
+        assert!(!joint.is_g2(0.0, 1e-3, 1e-6), "{:?}", joint);
+    }
+}
+
+#[test]
+fn circle_is_g2() {
+    let corners = [point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], point![-50.0, 50.0], point![-50.0, 0.0]];
+    let corners = [
+        corners.as_slice(),
+        &[point![-50.0, -50.0], point![0.0, -50.0], point![50.0, -50.0], point![50.0, 0.0]],
+    ]
+    .concat();
+    let circle: Vec<Conic> = corners.windows(3).step_by(2).map(|w| Conic::new(w[0], w[1], w[2], FRAC_1_SQRT_2)).collect();
+    let joints = circle.joints(true);
+    assert_eq!(joints.len(), 4);
+    assert_eq!(joints[3].index, 3);
+    for joint in joints {
+        assert!(joint.is_g2(1e-4, 1e-4, 1e-5), "{:?}", joint);
+    }
+}
+
+#[test]
+fn corners_and_gaps() {
+    // A square corner, then a gap.
+    let spline: CubicSpline = vec![
+        [point![0.0, 0.0], point![10.0, 0.0], point![20.0, 0.0], point![30.0, 0.0]],
+        [point![30.0, 0.0], point![30.0, 10.0], point![30.0, 20.0], point![30.0, 30.0]],
+        [point![30.0, 33.0], point![30.0, 40.0], point![30.0, 50.0], point![30.0, 60.0]],
+    ];
+    let joints = spline.joints(false);
+    assert_eq!(joints.len(), 2);
+    assert!((joints[0].angle - FRAC_PI_2).abs() < 1e-6);
+    assert_eq!(joints[0].gap, 0.0);
+    assert!(!joints[0].is_g1(0.0, 1e-3));
+    assert_eq!(joints[1].angle, 0.0);
+    assert_eq!(joints[1].gap, 3.0);
+    assert!(joints[1].is_g1(3.0, 0.0) && !joints[1].is_g1(1.0, 0.0));
+    // Closing the spline adds the joint back to the start.
+    assert_eq!(spline.joints(true).len(), 3);
+    assert!((spline.joints(true)[2].gap - 67.08204).abs() < 1e-3);
+
+    let empty: QuadSpline = vec![];
+    assert!(empty.joints(true).is_empty());
+}
+
+#[test]
+fn smooth_cubic_joints() {
+    // Two joints whose handles are slightly off collinear; only the first is marked smooth.
+    let mut spline: CubicSpline = vec![
+        [point![0.0, 0.0], point![10.0, 20.0], point![40.0, 30.0], point![50.0, 30.0]],
+        [point![50.0, 30.0], point![70.0, 30.5], point![80.0, 10.0], point![90.0, 0.0]],
+        [point![90.0, 0.0], point![101.0, -10.0], point![110.0, -10.0], point![120.0, 0.0]],
+    ];
+    let before = spline.clone();
+    assert!(spline.joints(false).iter().all(|j| j.angle > 1e-3));
+    spline.smooth_joints(&[0]);
+    let joints = spline.joints(false);
+    assert!(joints[0].is_g1(0.0, 1e-5), "{:?}", joints[0]);
+    assert_eq!(spline[1][2..], before[1][2..]);
+    assert_eq!(spline[2], before[2]);
+    // The on-curve point stays, the handles keep their lengths and the longer one moves less.
+    assert_eq!(spline[0][3], before[0][3]);
+    assert!(((spline[0][2] - spline[0][3]).norm() - 10.0).abs() < 1e-4);
+    assert!(((spline[1][1] - spline[1][0]).norm() - (before[1][1] - before[1][0]).norm()).abs() < 1e-4);
+    assert!((spline[1][1] - before[1][1]).norm() < (spline[0][2] - before[0][2]).norm());
+    // The tangents at the joint depend on the whole of both cubics, so may differ in the last bit.
+    assert!((joints[1].angle - before.joints(false)[1].angle).abs() < 1e-6);
+
+    // Closing joint.
+    let mut closed = spline.clone();
+    closed[2][3] = point![0.0, 0.5];
+    closed.smooth_joints(&[2]);
+    assert!(closed.joints(true)[2].is_g1(0.0, 1e-5));
+    // Joints past the closing one are left alone.
+    let smoothed = closed.clone();
+    closed.smooth_joints(&[3, 10]);
+    assert_eq!(closed, smoothed);
+}
+
+#[test]
+fn smooth_cusp() {
+    // Both handles are the same, so the spline turns back on itself at the joint.
+    let mut spline: CubicSpline = vec![
+        [point![0.0, 0.0], point![50.0, 0.0], point![100.0, 50.0], point![100.0, 0.0]],
+        [point![100.0, 0.0], point![100.0, 50.0], point![150.0, 0.0], point![200.0, 0.0]],
+    ];
+    assert!(spline.joints(false)[0].angle > 3.0);
+    spline.smooth_joints(&[0]);
+    assert!(spline.joints(false)[0].is_g1(0.0, 1e-5), "{:?}", spline.joints(false)[0]);
+    // The handles lie along the chord from the start to the end, and keep their lengths.
+    assert_eq!(spline[0][2], point![50.0, 0.0]);
+    assert_eq!(spline[1][1], point![150.0, 0.0]);
+}
+
+#[test]
+fn smooth_quad_and_conic_joints() {
+    let mut quads: QuadSpline = vec![
+        [point![0.0, 0.0], point![10.0, 20.0], point![30.0, 20.5]],
+        [point![30.0, 20.5], point![50.0, 20.0], point![60.0, 0.0]],
+    ];
+    quads.smooth_joints(&[0]);
+    assert!(quads.joints(false)[0].is_g1(0.0, 1e-5));
+    assert_eq!(quads[0][2], point![30.0, 20.0]);
+    assert_eq!(quads[0][1], point![10.0, 20.0]);
+    assert_eq!(quads[1][1], point![50.0, 20.0]);
+
+    // A drifted and slightly open circle.
+    let corners = [point![50.0, 0.0], point![50.0, 50.0], point![0.1, 50.0], point![-50.0, 50.0], point![-50.0, 0.0]];
+    let mut arcs: Vec<Conic> = corners.windows(3).step_by(2).map(|w| Conic::new(w[0], w[1], w[2], FRAC_1_SQRT_2)).collect();
+    arcs[1].start = point![0.0, 50.2];
+    assert!(!arcs.joints(false)[0].is_g1(1e-4, 1e-4));
+    arcs.smooth_joints(&[0]);
+    assert!(arcs.joints(false)[0].is_g1(0.0, 1e-5));
+    assert!((arcs[0].end - point![0.1, 50.0]).norm() < 1e-5);
+}
@@ -3,80 +3,364 @@ use cucoqu::point;
 use cucoqu::Cubic;
 use cucoqu::Point;
 use cucoqu::Quad;
+use cucoqu::Scalar;
 
-fn expected_result() -> [[Quad; 5]; 4] {
+mod common;
+use common::{random_cubic, Rng};
+
+fn assert_near(a: Point, b: Point) {
+    assert!((a - b).norm() < 1e-3, "{:?} != {:?}", a, b);
+}
+
+fn expected_result() -> [[Quad; 19]; 4] {
     [
         [
-            [point![50.0, 0.0], point![46.45721, 19.64031], point![39.897583, 30.799238]],
+            [point![50.0, 0.0], point![50.0, 2.178612], point![49.816917, 4.3109794]],
+            [
+                point![49.816917, 4.3109794],
+                point![49.63384, 6.4433465],
+                point![49.275864, 8.521278],
+            ],
+            [
+                point![49.275864, 8.521278],
+                point![48.917885, 10.599211],
+                point![48.392548, 12.615177],
+            ],
+            [
+                point![48.392548, 12.615177],
+                point![47.867207, 14.631145],
+                point![47.182034, 16.577621],
+            ],
             [
-                point![39.897583, 30.799238],
-                point![39.897583, 30.799238],
-                point![30.799238, 39.897575],
+                point![47.182034, 16.577621],
+                point![46.49686, 18.524097],
+                point![45.65938, 20.39355],
             ],
             [
-                point![30.799238, 39.897575],
-                point![30.799238, 39.897575],
-                point![19.640306, 46.457207],
+                point![45.65938, 20.39355],
+                point![44.821903, 22.263004],
+                point![43.839645, 24.047909],
             ],
-            [point![19.640306, 46.457207], point![19.640306, 46.457207], point![6.898932, 50.0]],
-            [point![6.898932, 50.0], point![6.898932, 50.0], point![0.0, 50.0]],
+            [
+                point![43.839645, 24.047909],
+                point![42.857388, 25.832813],
+                point![41.737877, 27.525639],
+            ],
+            [
+                point![41.737877, 27.525639],
+                point![40.61837, 29.218464],
+                point![39.36914, 30.811684],
+            ],
+            [
+                point![39.36914, 30.811684],
+                point![38.11991, 32.404903],
+                point![36.748493, 33.890987],
+            ],
+            [
+                point![36.748493, 33.890987],
+                point![35.377075, 35.37707],
+                point![33.89099, 36.74849],
+            ],
+            [point![33.89099, 36.74849], point![32.404903, 38.11991], point![30.811684, 39.36914]],
+            [
+                point![30.811684, 39.36914],
+                point![29.218464, 40.61837],
+                point![27.525639, 41.737877],
+            ],
+            [
+                point![27.525639, 41.737877],
+                point![25.832815, 42.85738],
+                point![24.047909, 43.83964],
+            ],
+            [
+                point![24.047909, 43.83964],
+                point![22.263002, 44.821903],
+                point![20.39355, 45.659378],
+            ],
+            [
+                point![20.39355, 45.659378],
+                point![18.524097, 46.496857],
+                point![16.577621, 47.18203],
+            ],
+            [
+                point![16.577621, 47.18203],
+                point![14.631144, 47.867207],
+                point![12.615177, 48.392548],
+            ],
+            [
+                point![12.615177, 48.392548],
+                point![10.599211, 48.917885],
+                point![8.52128, 49.275864],
+            ],
+            [point![8.52128, 49.275864], point![6.443351, 49.63384], point![4.31098, 49.816917]],
+            [point![4.31098, 49.816917], point![2.1786094, 49.999996], point![0.0, 50.0]],
         ],
         [
-            [point![0.0, 50.0], point![-19.64031, 46.45721], point![-30.799238, 39.897583]],
+            [point![0.0, 50.0], point![-2.178612, 50.0], point![-4.3109794, 49.816917]],
+            [
+                point![-4.3109794, 49.816917],
+                point![-6.4433465, 49.63384],
+                point![-8.521278, 49.275864],
+            ],
+            [
+                point![-8.521278, 49.275864],
+                point![-10.599211, 48.917885],
+                point![-12.615177, 48.392548],
+            ],
+            [
+                point![-12.615177, 48.392548],
+                point![-14.631145, 47.867207],
+                point![-16.577621, 47.182034],
+            ],
             [
-                point![-30.799238, 39.897583],
-                point![-30.799238, 39.897583],
-                point![-39.897575, 30.799238],
+                point![-16.577621, 47.182034],
+                point![-18.524097, 46.49686],
+                point![-20.39355, 45.65938],
             ],
             [
-                point![-39.897575, 30.799238],
-                point![-39.897575, 30.799238],
-                point![-46.457207, 19.640306],
+                point![-20.39355, 45.65938],
+                point![-22.263004, 44.821903],
+                point![-24.047909, 43.839645],
             ],
             [
-                point![-46.457207, 19.640306],
-                point![-46.457207, 19.640306],
-                point![-50.0, 6.898932],
+                point![-24.047909, 43.839645],
+                point![-25.832813, 42.857388],
+                point![-27.525639, 41.737877],
             ],
-            [point![-50.0, 6.898932], point![-50.0, 6.898932], point![-50.0, 0.0]],
+            [
+                point![-27.525639, 41.737877],
+                point![-29.218464, 40.61837],
+                point![-30.811684, 39.36914],
+            ],
+            [
+                point![-30.811684, 39.36914],
+                point![-32.404903, 38.11991],
+                point![-33.890987, 36.748493],
+            ],
+            [
+                point![-33.890987, 36.748493],
+                point![-35.37707, 35.377075],
+                point![-36.74849, 33.89099],
+            ],
+            [
+                point![-36.74849, 33.89099],
+                point![-38.11991, 32.404903],
+                point![-39.36914, 30.811684],
+            ],
+            [
+                point![-39.36914, 30.811684],
+                point![-40.61837, 29.218464],
+                point![-41.737877, 27.525639],
+            ],
+            [
+                point![-41.737877, 27.525639],
+                point![-42.85738, 25.832815],
+                point![-43.83964, 24.047909],
+            ],
+            [
+                point![-43.83964, 24.047909],
+                point![-44.821903, 22.263002],
+                point![-45.659378, 20.39355],
+            ],
+            [
+                point![-45.659378, 20.39355],
+                point![-46.496857, 18.524097],
+                point![-47.18203, 16.577621],
+            ],
+            [
+                point![-47.18203, 16.577621],
+                point![-47.867207, 14.631144],
+                point![-48.392548, 12.615177],
+            ],
+            [
+                point![-48.392548, 12.615177],
+                point![-48.917885, 10.599211],
+                point![-49.275864, 8.52128],
+            ],
+            [
+                point![-49.275864, 8.52128],
+                point![-49.63384, 6.443351],
+                point![-49.816917, 4.31098],
+            ],
+            [point![-49.816917, 4.31098], point![-49.999996, 2.1786094], point![-50.0, 0.0]],
         ],
         [
-            [point![-50.0, 0.0], point![-46.45721, -19.64031], point![-39.897583, -30.799238]],
+            [point![-50.0, 0.0], point![-50.0, -2.178612], point![-49.816917, -4.3109794]],
             [
-                point![-39.897583, -30.799238],
-                point![-39.897583, -30.799238],
-                point![-30.799238, -39.897575],
+                point![-49.816917, -4.3109794],
+                point![-49.63384, -6.4433465],
+                point![-49.275864, -8.521278],
             ],
             [
-                point![-30.799238, -39.897575],
-                point![-30.799238, -39.897575],
-                point![-19.640306, -46.457207],
+                point![-49.275864, -8.521278],
+                point![-48.917885, -10.599211],
+                point![-48.392548, -12.615177],
             ],
             [
-                point![-19.640306, -46.457207],
-                point![-19.640306, -46.457207],
-                point![-6.898932, -50.0],
+                point![-48.392548, -12.615177],
+                point![-47.867207, -14.631145],
+                point![-47.182034, -16.577621],
             ],
-            [point![-6.898932, -50.0], point![-6.898932, -50.0], point![-0.0, -50.0]],
+            [
+                point![-47.182034, -16.577621],
+                point![-46.49686, -18.524097],
+                point![-45.65938, -20.39355],
+            ],
+            [
+                point![-45.65938, -20.39355],
+                point![-44.821903, -22.263004],
+                point![-43.839645, -24.047909],
+            ],
+            [
+                point![-43.839645, -24.047909],
+                point![-42.857388, -25.832813],
+                point![-41.737877, -27.525639],
+            ],
+            [
+                point![-41.737877, -27.525639],
+                point![-40.61837, -29.218464],
+                point![-39.36914, -30.811684],
+            ],
+            [
+                point![-39.36914, -30.811684],
+                point![-38.11991, -32.404903],
+                point![-36.748493, -33.890987],
+            ],
+            [
+                point![-36.748493, -33.890987],
+                point![-35.377075, -35.37707],
+                point![-33.89099, -36.74849],
+            ],
+            [
+                point![-33.89099, -36.74849],
+                point![-32.404903, -38.11991],
+                point![-30.811684, -39.36914],
+            ],
+            [
+                point![-30.811684, -39.36914],
+                point![-29.218464, -40.61837],
+                point![-27.525639, -41.737877],
+            ],
+            [
+                point![-27.525639, -41.737877],
+                point![-25.832815, -42.85738],
+                point![-24.047909, -43.83964],
+            ],
+            [
+                point![-24.047909, -43.83964],
+                point![-22.263002, -44.821903],
+                point![-20.39355, -45.659378],
+            ],
+            [
+                point![-20.39355, -45.659378],
+                point![-18.524097, -46.496857],
+                point![-16.577621, -47.18203],
+            ],
+            [
+                point![-16.577621, -47.18203],
+                point![-14.631144, -47.867207],
+                point![-12.615177, -48.392548],
+            ],
+            [
+                point![-12.615177, -48.392548],
+                point![-10.599211, -48.917885],
+                point![-8.52128, -49.275864],
+            ],
+            [
+                point![-8.52128, -49.275864],
+                point![-6.443351, -49.63384],
+                point![-4.31098, -49.816917],
+            ],
+            [point![-4.31098, -49.816917], point![-2.1786094, -49.999996], point![-0.0, -50.0]],
         ],
         [
-            [point![-0.0, -50.0], point![19.64031, -46.45721], point![30.799238, -39.897583]],
+            [point![-0.0, -50.0], point![2.178612, -50.0], point![4.3109794, -49.816917]],
+            [
+                point![4.3109794, -49.816917],
+                point![6.4433465, -49.63384],
+                point![8.521278, -49.275864],
+            ],
+            [
+                point![8.521278, -49.275864],
+                point![10.599211, -48.917885],
+                point![12.615177, -48.392548],
+            ],
+            [
+                point![12.615177, -48.392548],
+                point![14.631145, -47.867207],
+                point![16.577621, -47.182034],
+            ],
+            [
+                point![16.577621, -47.182034],
+                point![18.524097, -46.49686],
+                point![20.39355, -45.65938],
+            ],
+            [
+                point![20.39355, -45.65938],
+                point![22.263004, -44.821903],
+                point![24.047909, -43.839645],
+            ],
+            [
+                point![24.047909, -43.839645],
+                point![25.832813, -42.857388],
+                point![27.525639, -41.737877],
+            ],
+            [
+                point![27.525639, -41.737877],
+                point![29.218464, -40.61837],
+                point![30.811684, -39.36914],
+            ],
+            [
+                point![30.811684, -39.36914],
+                point![32.404903, -38.11991],
+                point![33.890987, -36.748493],
+            ],
+            [
+                point![33.890987, -36.748493],
+                point![35.37707, -35.377075],
+                point![36.74849, -33.89099],
+            ],
+            [
+                point![36.74849, -33.89099],
+                point![38.11991, -32.404903],
+                point![39.36914, -30.811684],
+            ],
             [
-                point![30.799238, -39.897583],
-                point![30.799238, -39.897583],
-                point![39.897575, -30.799238],
+                point![39.36914, -30.811684],
+                point![40.61837, -29.218464],
+                point![41.737877, -27.525639],
             ],
             [
-                point![39.897575, -30.799238],
-                point![39.897575, -30.799238],
-                point![46.457207, -19.640306],
+                point![41.737877, -27.525639],
+                point![42.85738, -25.832815],
+                point![43.83964, -24.047909],
             ],
             [
-                point![46.457207, -19.640306],
-                point![46.457207, -19.640306],
-                point![50.0, -6.898932],
+                point![43.83964, -24.047909],
+                point![44.821903, -22.263002],
+                point![45.659378, -20.39355],
             ],
-            [point![50.0, -6.898932], point![50.0, -6.898932], point![50.0, 0.0]],
+            [
+                point![45.659378, -20.39355],
+                point![46.496857, -18.524097],
+                point![47.18203, -16.577621],
+            ],
+            [
+                point![47.18203, -16.577621],
+                point![47.867207, -14.631144],
+                point![48.392548, -12.615177],
+            ],
+            [
+                point![48.392548, -12.615177],
+                point![48.917885, -10.599211],
+                point![49.275864, -8.52128],
+            ],
+            [
+                point![49.275864, -8.52128],
+                point![49.63384, -6.443351],
+                point![49.816917, -4.31098],
+            ],
+            [point![49.816917, -4.31098], point![49.999996, -2.1786094], point![50.0, 0.0]],
         ],
     ]
 }
@@ -113,5 +397,172 @@ fn test_convert() {
     let output = TEST_DATA.to_vec();
     let output = output.curves_to_quadratic(vec![0.001; 4]).unwrap();
     //eprintln!("{:?}", &output);
-    assert_eq!(&output, &expected_result());
+    // The expected quads were computed with f32, so f64 comes near them rather than to them.
+    let expected = expected_result();
+    assert_eq!(output.iter().map(Vec::len).collect::<Vec<_>>(), expected.iter().map(|spline| spline.len()).collect::<Vec<_>>());
+    for (quad, expected) in output.iter().flatten().zip(expected.iter().flatten()) {
+        quad.iter().zip(expected).for_each(|(&a, &b)| assert_near(a, b));
+    }
+}
+
+#[test]
+fn test_split_at_extrema() {
+    use cucoqu::cu2qu::{CurveToQuadratic, SplitOptions};
+    // Extrema: y at t=0.5 (50, 75); x at both ends of the loop-like overshoots.
+    static TEST_DATA: &[Cubic] = &[
+        [point![0.0, 0.0], point![0.0, 100.0], point![100.0, 100.0], point![100.0, 0.0]],
+        [point![0.0, 0.0], point![-50.0, 80.0], point![150.0, 50.0], point![100.0, 0.0]],
+    ];
+    let options = SplitOptions { at_extrema: true, ..Default::default() };
+    for cubic in TEST_DATA {
+        let spline = cubic.curve_to_quadratic_with(0.1, &options).unwrap();
+        assert_eq!(spline[0][0], cubic[0]);
+        assert_eq!(spline.last().unwrap()[2], cubic[3]);
+        // Every extremum is a joint whose neighbouring control points are level with it.
+        let mut found_extrema = 0;
+        for w in spline.windows(2) {
+            let (before, joint, after) = (w[0][1], w[0][2], w[1][1]);
+            assert_eq!(joint, w[1][0]);
+            if before[1] == joint[1] && after[1] == joint[1] || before[0] == joint[0] && after[0] == joint[0] {
+                found_extrema += 1;
+            }
+        }
+        assert!(found_extrema > 0);
+        // No quad overshoots its own endpoints, so every extremum is on-curve.
+        for quad in &spline {
+            for axis in 0..2 {
+                let (lo, hi) = (quad[0][axis].min(quad[2][axis]), quad[0][axis].max(quad[2][axis]));
+                assert!(quad[1][axis] >= lo - 1e-3 && quad[1][axis] <= hi + 1e-3, "{:?}", quad);
+            }
+        }
+    }
+    let arch = TEST_DATA[0].curve_to_quadratic_with(0.1, &options).unwrap();
+    assert!(arch.iter().any(|q| (q[2] - point![50.0, 75.0]).norm() < 1e-3));
+    let plain = TEST_DATA[0].curve_to_quadratic_with(0.1, &SplitOptions::default()).unwrap();
+    assert_eq!(plain, TEST_DATA[0].curve_to_quadratic(0.1).unwrap());
+}
+
+#[test]
+fn test_split_at_inflections() {
+    use cucoqu::co2qu::EvalAt;
+    use cucoqu::cu2qu::{ClassifyCubic, CurveToQuadratic, SplitOptions};
+    let options = SplitOptions { at_inflections: true, ..Default::default() };
+    let s_curve: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]];
+    // P′(½) = 0 when P2 = P1 + P0 − P3.
+    let cusp: Cubic = [point![0.0, 0.0], point![150.0, 100.0], point![50.0, 100.0], point![100.0, 0.0]];
+    for (cubic, t) in [(s_curve, s_curve.inflections()[0]), (cusp, cusp.cusp().unwrap())] {
+        let spline = cubic.curve_to_quadratic_with(0.1, &options).unwrap();
+        assert_eq!(spline[0][0], cubic[0]);
+        assert_eq!(spline.last().unwrap()[2], cubic[3]);
+        for w in spline.windows(2) {
+            assert_eq!(w[0][2], w[1][0]);
+        }
+        // The inflection (or cusp) is an on-curve point.
+        let split_point = cubic.eval_at(t);
+        assert!(spline.iter().any(|q| (q[2] - split_point).norm() < 1e-3));
+    }
+    // A degree-elevated quadratic is found again in one piece.
+    let quadratic: Cubic = [point![0.0, 0.0], point![20.0, 40.0], point![50.0, 40.0], point![90.0, 0.0]];
+    assert_eq!(quadratic.curve_to_quadratic(0.01).unwrap(), vec![[point![0.0, 0.0], point![30.0, 60.0], point![90.0, 0.0]]]);
+}
+
+#[test]
+fn test_tolerance_is_a_distance() {
+    use cucoqu::co2qu::{max_distance, Conic};
+    use cucoqu::cu2qu::CurveToQuadratic;
+    use cucoqu::scalar_consts::FRAC_1_SQRT_2;
+    // The same quarter circle, as a cubic and as a conic.
+    let cubic: Cubic = [point![500.0, 0.0], point![500.0, 275.95752], point![275.95752, 500.0], point![0.0, 500.0]];
+    let conic = Conic::new(point![500.0, 0.0], point![500.0, 500.0], point![0.0, 500.0], FRAC_1_SQRT_2);
+    for tolerance in [0.01, 0.1, 1.0, 10.0] {
+        let from_cubic = max_distance(&cubic, &cubic.curve_to_quadratic(tolerance).unwrap()).distance;
+        let from_conic = max_distance(&conic, &conic.as_quads(tolerance)).distance;
+        assert!(from_cubic <= tolerance && from_cubic > tolerance / 10.0, "{} {}", tolerance, from_cubic);
+        // Skia's estimate of the conic's error is conservative, so it may come much closer.
+        assert!(from_conic <= tolerance, "{} {}", tolerance, from_conic);
+    }
+}
+
+#[test]
+fn test_adaptive_split() {
+    use cucoqu::co2qu::verify;
+    use cucoqu::cu2qu::{CurveToQuadratic, SplitOptions};
+    let options = SplitOptions {
+        adaptive: true,
+        ..Default::default()
+    };
+    static TEST_DATA: &[Cubic] = &[
+        // Curvature concentrated near the end.
+        [point![0.0, 0.0], point![300.0, 0.0], point![400.0, 0.0], point![400.0, 100.0]],
+        [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]],
+    ];
+    for cubic in TEST_DATA {
+        let uniform = cubic.curve_to_quadratic(0.1).unwrap();
+        let adaptive = cubic.curve_to_quadratic_with(0.1, &options).unwrap();
+        assert!(adaptive.len() < uniform.len(), "{} {}", adaptive.len(), uniform.len());
+        assert_eq!(verify(cubic, &adaptive, 0.1), Ok(()));
+        // The on-curve points between the quads are still implied.
+        for pair in adaptive.windows(2) {
+            assert_eq!(pair[0][2], pair[1][0]);
+            assert_eq!(pair[0][2], (pair[0][1] + pair[1][1]) * 0.5);
+        }
+    }
+}
+
+#[test]
+fn test_fewest_quads() {
+    use cucoqu::cu2qu::{CubicApprox, CurveToQuadratic, CurvesToQuadratic};
+    let mut rng = Rng::default();
+    let mut previous: Option<Cubic> = None;
+    for _ in 0..200 {
+        let cubic = random_cubic(&mut rng);
+        for tolerance in [0.1, 1.0] {
+            // Trying every number of quads in turn, as fontTools does.
+            let expected = (1..=100).find_map(|n| cubic.approx_spline(n, tolerance).ok());
+            assert_eq!(cubic.curve_to_quadratic(tolerance).ok(), expected, "{:?}", cubic);
+            // And for the same number of quads for both of a pair of cubics.
+            let pair: Vec<Cubic> = previous.into_iter().chain([cubic]).collect();
+            let expected = (1..=100).find_map(|n| pair.iter().map(|cubic| cubic.approx_spline(n, tolerance).ok()).collect());
+            assert_eq!(pair.curves_to_quadratic(vec![tolerance; pair.len()]).ok(), expected, "{:?}", pair);
+        }
+        previous = Some(cubic);
+    }
+}
+
+#[test]
+fn test_into_buffer() {
+    use cucoqu::co2qu::Conic;
+    use cucoqu::cu2qu::{CurveToQuadratic, MAX_N};
+    let cubic: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]];
+    let mut buffer = [[Point::default(); 3]; MAX_N];
+    let n = cubic.curve_to_quadratic_into(0.1, &mut buffer).unwrap();
+    assert_eq!(buffer[..n], cubic.curve_to_quadratic(0.1).unwrap()[..]);
+    assert!(cubic.curve_to_quadratic_into(0.1, &mut buffer[..n - 1]).is_err());
+    assert_eq!(cubic.curve_to_quadratic_into(0.1, &mut buffer[..n]).unwrap(), n);
+
+    let conic = Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], cucoqu::scalar_consts::FRAC_1_SQRT_2);
+    let quads = conic.as_quads(0.01);
+    let n = conic.as_quads_into(0.01, &mut buffer).unwrap();
+    assert_eq!(buffer[..n], quads[..]);
+    assert!(conic.as_quads_into(0.01, &mut buffer[..n - 1]).is_err());
+}
+
+#[test]
+fn test_adaptive_split_within_tolerance() {
+    use cucoqu::co2qu::max_distance;
+    use cucoqu::cu2qu::{CurveToQuadratic, SplitOptions};
+    let options = SplitOptions {
+        adaptive: true,
+        ..Default::default()
+    };
+    let mut rng = Rng::default();
+    // Coordinates up to 1000 are only exact to within this.
+    let rounding = 1000.0 * Scalar::EPSILON;
+    for i in 0..15 {
+        let cubic = random_cubic(&mut rng);
+        let tolerance = [1.0, 0.25, 0.1][i % 3];
+        let adaptive = cubic.curve_to_quadratic_with(tolerance, &options).unwrap();
+        let deviation = max_distance(&cubic, &adaptive);
+        assert!(deviation.distance <= tolerance + rounding, "{:?} for {:?} within {}", deviation, cubic, tolerance);
+    }
 }
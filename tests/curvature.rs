@@ -0,0 +1,93 @@
+use cucoqu::co2qu::{Conic, Curvature, EvalAt, EvalTangentAt};
+use cucoqu::point;
+use cucoqu::{Cubic, Point, Quad, Scalar};
+
+mod common;
+use common::assert_close;
+
+#[test]
+fn circle_curvature() {
+    // A quarter circle of radius 50, counterclockwise.
+    let conic = Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], cucoqu::scalar_consts::FRAC_1_SQRT_2);
+    for (t, kappa) in conic.curvature_profile(10) {
+        assert!((conic.eval_at(t).norm() - 50.0).abs() < 1e-3);
+        assert_close(kappa, 1.0 / 50.0, 1e-5);
+    }
+    // The same quarter circle, clockwise.
+    let reversed = Conic::new(point![0.0, 50.0], point![50.0, 50.0], point![50.0, 0.0], cucoqu::scalar_consts::FRAC_1_SQRT_2);
+    assert_close(reversed.curvature_at(0.3), -1.0 / 50.0, 1e-5);
+    // The tangent is perpendicular to the radius.
+    for t in [0.0, 0.25, 0.5, 1.0] {
+        assert_close(conic.eval_tangent_at(t).normalize().dot(&conic.eval_at(t).normalize()), 0.0, 1e-5);
+    }
+
+    // The 0.5519… cubic approximation has nearly constant curvature.
+    let k = 50.0 * 0.551_915;
+    let cubic: Cubic = [point![50.0, 0.0], point![50.0, k], point![k, 50.0], point![0.0, 50.0]];
+    for (_, kappa) in cubic.curvature_profile(10) {
+        assert_close(kappa, 1.0 / 50.0, 1e-3);
+    }
+}
+
+#[test]
+fn quad_max_curvature() {
+    // y = x² around the vertex at t=¼, where κ = 2.
+    let quad: Quad = [point![-1.0, 1.0], point![1.0, -3.0], point![3.0, 9.0]];
+    let t = quad.max_curvature();
+    assert_eq!(t.len(), 1);
+    assert_close(t[0], 0.25, 1e-6);
+    assert!((quad.eval_at(t[0]) - point![0.0, 0.0]).norm() < 1e-5);
+    assert_close(quad.curvature_at(t[0]), 2.0, 1e-4);
+    // The right half of the parabola is curved most at its start.
+    let half: Quad = [point![0.0, 0.0], point![1.0, 0.0], point![2.0, 4.0]];
+    assert_eq!(half.max_curvature(), vec![0.0]);
+    assert_close(half.curvature_at(0.0), 2.0, 1e-4);
+}
+
+#[test]
+fn cubic_max_curvature() {
+    // Symmetric arch: greatest curvature at the top.
+    let arch: Cubic = [point![0.0, 0.0], point![0.0, 100.0], point![100.0, 100.0], point![100.0, 0.0]];
+    let t_values = arch.max_curvature();
+    assert!(t_values.iter().any(|&t| (t - 0.5).abs() < 1e-4));
+    let top = arch.curvature_at(0.5);
+    assert!(top < 0.0, "the arch turns clockwise");
+    for i in 0..=20 {
+        assert!(arch.curvature_at(i as Scalar / 20.0).abs() <= top.abs() + 1e-6);
+    }
+    // At a cusp the derivative vanishes and the curvature is not finite.
+    let cusp: Cubic = [point![0.0, 0.0], point![150.0, 100.0], point![50.0, 100.0], point![100.0, 0.0]];
+    assert!(cusp.max_curvature().iter().any(|&t| (t - 0.5).abs() < 1e-3));
+    assert_eq!(cusp.eval_tangent_at(0.5), point![0.0, 0.0]);
+    assert!(!cusp.curvature_at(0.5).is_finite());
+}
+
+#[test]
+fn conic_max_curvature() {
+    // Half of an ellipse with semi-axes 100 and 50: greatest curvature at the ends of the major
+    // axis, least at the minor axis.
+    let quarter = Conic::new(point![100.0, 0.0], point![100.0, 50.0], point![0.0, 50.0], cucoqu::scalar_consts::FRAC_1_SQRT_2);
+    assert_eq!(quarter.max_curvature(), vec![0.0]);
+    assert_close(quarter.curvature_at(0.0), 100.0 / (50.0 * 50.0), 1e-5);
+    assert_close(quarter.curvature_at(1.0), 50.0 / (100.0 * 100.0), 1e-5);
+
+    // A hyperbola is curved most at its vertex.
+    let hyperbola = Conic::new(point![-50.0, 40.0], point![0.0, -20.0], point![50.0, 40.0], 2.0);
+    let t_values = hyperbola.max_curvature();
+    assert_eq!(t_values.len(), 1);
+    assert_close(t_values[0], 0.5, 1e-3);
+    // A parabola agrees with the quad.
+    let quad: Quad = [point![0.0, 0.0], point![80.0, 10.0], point![20.0, 90.0]];
+    let parabola = Conic::new(quad[0], quad[1], quad[2], 1.0);
+    assert_close(parabola.max_curvature()[0], quad.max_curvature()[0], 1e-3);
+    assert_close(parabola.curvature_at(0.4), quad.curvature_at(0.4), 1e-5);
+
+    // A circular arc of 120°, whose weight is the cosine of half its angle, is curved as much
+    // everywhere.
+    let sqrt_3 = Scalar::sqrt(3.0);
+    let arc = Conic::new(point![100.0, 0.0], point![100.0, 100.0 * sqrt_3], point![-50.0, 50.0 * sqrt_3], 0.5);
+    for i in 0..=10 {
+        assert_close(arc.curvature_at(i as Scalar / 10.0), 0.01, 1e-6);
+    }
+    assert_eq!(arc.max_curvature(), vec![]);
+}
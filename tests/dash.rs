@@ -0,0 +1,99 @@
+use cucoqu::co2qu::{ArcLength, Conic, Dash, EvalAt};
+use cucoqu::point;
+use cucoqu::{CubicSpline, Point, QuadSpline, Scalar};
+
+use cucoqu::scalar_consts::{FRAC_1_SQRT_2, PI};
+
+fn assert_near(a: Point, b: Point) {
+    assert!((a - b).norm() < 1e-3, "{:?} != {:?}", a, b);
+}
+
+fn corner() -> CubicSpline {
+    vec![
+        [point![0.0, 0.0], point![10.0, 0.0], point![20.0, 0.0], point![30.0, 0.0]],
+        [point![30.0, 0.0], point![30.0, 10.0], point![30.0, 20.0], point![30.0, 40.0]],
+    ]
+}
+
+/// The point `d` units along the corner.
+fn along(d: Scalar) -> Point {
+    if d < 30.0 {
+        point![d, 0.0]
+    } else {
+        point![30.0, d - 30.0]
+    }
+}
+
+fn assert_dashes(dashes: &[CubicSpline], expected: &[(Scalar, Scalar)]) {
+    assert_eq!(dashes.len(), expected.len());
+    for (dash, &(start, end)) in dashes.iter().zip(expected) {
+        assert_near(dash[0][0], along(start));
+        assert_near(dash.last().unwrap()[3], along(end));
+        assert!((dash.arc_length(1e-4) - (end - start)).abs() < 1e-3);
+        for w in dash.windows(2) {
+            assert_eq!(w[0][3], w[1][0]);
+        }
+    }
+}
+
+#[test]
+fn dash_across_joints() {
+    let dashes = corner().dash(&[10.0, 5.0], 0.0, 1e-4);
+    assert_dashes(&dashes, &[(0.0, 10.0), (15.0, 25.0), (30.0, 40.0), (45.0, 55.0), (60.0, 70.0)]);
+    // A dash starting at the joint lies on the second segment only.
+    assert_eq!(dashes[2].len(), 1);
+    assert_eq!(dashes[0][0][0], point![0.0, 0.0]);
+    assert_eq!(dashes[4][0][3], point![30.0, 40.0]);
+
+    let dashes = corner().dash(&[20.0, 5.0], 0.0, 1e-4);
+    assert_dashes(&dashes, &[(0.0, 20.0), (25.0, 45.0), (50.0, 70.0)]);
+    assert_eq!(dashes[1].len(), 2);
+    assert_near(dashes[1][0][3], point![30.0, 0.0]);
+}
+
+#[test]
+fn dash_phase() {
+    let dashes = corner().dash(&[10.0, 5.0], 5.0, 1e-4);
+    assert_dashes(&dashes, &[(0.0, 5.0), (10.0, 20.0), (25.0, 35.0), (40.0, 50.0), (55.0, 65.0)]);
+    // Phase is taken modulo the pattern, and may be negative.
+    let dashes = corner().dash(&[10.0, 5.0], -5.0, 1e-4);
+    assert_dashes(&dashes, &[(5.0, 15.0), (20.0, 30.0), (35.0, 45.0), (50.0, 60.0), (65.0, 70.0)]);
+    assert_eq!(corner().dash(&[10.0, 5.0], 25.0, 1e-4).len(), dashes.len());
+
+    // Dots, for round caps.
+    let dots = corner().dash(&[0.0, 10.0], 0.0, 1e-4);
+    assert_eq!(dots.len(), 7);
+    assert_near(dots[6][0][0], along(60.0));
+    assert_near(dots[6][0][3], along(60.0));
+}
+
+#[test]
+fn dash_quads_and_conics() {
+    let quads: QuadSpline = vec![[point![0.0, 0.0], point![50.0, 0.0], point![100.0, 0.0]]];
+    let dashes = quads.dash(&[30.0, 10.0], 0.0, 1e-4);
+    assert_eq!(dashes.len(), 3);
+    assert_near(dashes[2][0][0], point![80.0, 0.0]);
+    assert_eq!(dashes[2][0][2], point![100.0, 0.0]);
+
+    let corners = [point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], point![-50.0, 50.0], point![-50.0, 0.0]];
+    let corners = [
+        corners.as_slice(),
+        &[point![-50.0, -50.0], point![0.0, -50.0], point![50.0, -50.0], point![50.0, 0.0]],
+    ]
+    .concat();
+    let circle: Vec<Conic> = corners.windows(3).step_by(2).map(|w| Conic::new(w[0], w[1], w[2], FRAC_1_SQRT_2)).collect();
+    // Eight dashes of 30° each, with gaps of 15°.
+    let unit = 50.0 * PI / 12.0;
+    let dashes = circle.dash(&[2.0 * unit, unit], 0.0, 1e-4);
+    assert_eq!(dashes.len(), 8);
+    for (i, dash) in dashes.iter().enumerate() {
+        let angle = (i as Scalar * 45.0).to_radians();
+        assert_near(dash[0].start, point![50.0 * angle.cos(), 50.0 * angle.sin()]);
+        assert!((dash.arc_length(1e-4) - 2.0 * unit).abs() < 1e-3);
+        for conic in dash {
+            for t in [0.25, 0.5, 0.75] {
+                assert!((conic.eval_at(t).norm() - 50.0).abs() < 1e-3);
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use cucoqu::co2qu::{area_difference, max_distance, Conic, EvalAt, Nearest};
+use cucoqu::cu2qu::CurveToQuadratic;
+use cucoqu::point;
+use cucoqu::qu2cu::QuadToCubic;
+use cucoqu::{Cubic, Point, Quad, Scalar};
+
+use cucoqu::scalar_consts::{FRAC_1_SQRT_2, PI};
+
+mod common;
+use common::{random_cubic, Rng};
+
+#[test]
+fn exact_conversions_do_not_deviate() {
+    let quad: Quad = [point![0.0, 0.0], point![80.0, 10.0], point![20.0, 90.0]];
+    let cubic: Cubic = quad.quad_to_cubic();
+    let deviation = max_distance(&quad, &[cubic]);
+    assert!(deviation.distance < 1e-3, "{:?}", deviation);
+    assert!(area_difference(&quad, &[cubic], 1e-3).abs() < 1e-2);
+}
+
+#[test]
+fn line_approximating_arc() {
+    // A semicircle of radius 50, approximated by its diameter.
+    let arc = [
+        Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], FRAC_1_SQRT_2),
+        Conic::new(point![0.0, 50.0], point![-50.0, 50.0], point![-50.0, 0.0], FRAC_1_SQRT_2),
+    ];
+    let diameter: Quad = [point![50.0, 0.0], point![0.0, 0.0], point![-50.0, 0.0]];
+    // Farthest at the top of the arc, at the end of the first conic.
+    let deviation = max_distance(&diameter, &arc);
+    assert!((deviation.distance - 50.0).abs() < 1e-3, "{:?}", deviation);
+    assert!((deviation.t - 0.5).abs() < 1e-3, "{:?}", deviation);
+    // The diameter lies to the left of (inside) the counterclockwise arc.
+    let area = area_difference(&diameter, &arc, 1e-3);
+    assert!((area + PI * 50.0 * 50.0 / 2.0).abs() < 1e-2, "{}", area);
+    // A quarter circle bulges out of its chord.
+    let chord: Quad = [point![50.0, 0.0], point![25.0, 25.0], point![0.0, 50.0]];
+    let area = area_difference(&arc[0], &[chord], 1e-3);
+    assert!((area - (PI * 625.0 - 1250.0)).abs() < 1e-2, "{}", area);
+}
+
+#[test]
+fn deviation_of_the_approximation() {
+    // Every point of the line is on the approximation, but not the other way round: the
+    // approximation overshoots the end of the line, to x=112.5.
+    let line: Quad = [point![0.0, 0.0], point![50.0, 0.0], point![100.0, 0.0]];
+    let overshoot: Quad = [point![0.0, 0.0], point![150.0, 0.0], point![100.0, 0.0]];
+    let deviation = max_distance(&line, &[overshoot]);
+    assert!((deviation.distance - 12.5).abs() < 1e-3, "{:?}", deviation);
+    assert_eq!(deviation.t, 1.0);
+    assert!(max_distance(&overshoot, &[line]).distance > 12.49);
+}
+
+#[test]
+fn cu2qu_deviation_within_tolerance() {
+    let s_curve: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]];
+    let quads = s_curve.curve_to_quadratic(1.0).unwrap();
+    let deviation = max_distance(&s_curve, &quads);
+    assert!(deviation.distance > 0.1 && deviation.distance <= 1.0, "{:?}", deviation);
+    assert!((0.0..=1.0).contains(&deviation.t));
+}
+
+/// The greatest distance in either direction between points finely spaced along each curve and
+/// the other curve.
+fn brute_max_distance(cubic: &Cubic, quads: &[Quad]) -> Scalar {
+    let samples = |segment: &dyn EvalAt| {
+        (0..=400).map(|i| segment.eval_at(i as Scalar / 400.0)).collect::<Vec<_>>()
+    };
+    let from_cubic = samples(cubic).into_iter().map(|p| quads.nearest(p).distance);
+    let from_quads = quads.iter().flat_map(|quad| samples(quad)).map(|p| cubic.nearest(p).distance);
+    from_cubic.chain(from_quads).fold(0.0, Scalar::max)
+}
+
+#[test]
+fn cu2qu_deviation_of_random_cubics() {
+    let mut rng = Rng::default();
+    for _ in 0..12 {
+        let cubic = random_cubic(&mut rng);
+        let Ok(quads) = cubic.curve_to_quadratic(1.0) else { continue };
+        let deviation = max_distance(&cubic, &quads);
+        let brute = brute_max_distance(&cubic, &quads);
+        assert!(deviation.distance <= 1.0, "{:?} for {:?}", deviation, cubic);
+        assert!((deviation.distance - brute).abs() < 1e-2, "{:?} != {} for {:?}", deviation, brute, cubic);
+    }
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "f64")]
+
+use cucoqu::co2qu::{Chop, Conic, EvalAt};
+use cucoqu::cu2qu::CubicApprox;
+use cucoqu::point;
+use cucoqu::scalar_consts::FRAC_1_SQRT_2;
+use cucoqu::{Cubic, Point, Scalar};
+
+// Far enough from the origin that f32 can't tell apart points a unit apart.
+const ORIGIN: Point = point![3e7, -2e7];
+
+#[test]
+fn scalar_is_f64() {
+    assert_eq!(std::mem::size_of::<Scalar>(), 8);
+}
+
+#[test]
+fn chop_far_from_origin() {
+    let circle = |p: Point| ((p - ORIGIN).norm() - 100.0).abs();
+    let arc = Conic::new(
+        ORIGIN + point![100.0, 0.0],
+        ORIGIN + point![100.0, 100.0],
+        ORIGIN + point![0.0, 100.0],
+        FRAC_1_SQRT_2,
+    );
+    let mut dst = [Conic::default(), Conic::default()];
+    assert!(arc.chop_at(0.3, &mut dst));
+    assert_eq!(dst[0].end, dst[1].start);
+    assert!(circle(dst[0].end) < 1e-6, "{:?}", dst[0].end);
+    for part in &dst {
+        for i in 0..=10 {
+            let p = part.eval_at(i as Scalar / 10.0);
+            assert!(circle(p) < 1e-6, "{:?}", p);
+        }
+    }
+}
+
+#[test]
+fn intersect_far_from_origin() {
+    // A quad raised to a cubic is approximated by that quad, whose control point is where the
+    // cubic's end tangents meet.
+    let control = ORIGIN + point![100.0, 200.0];
+    let (start, end) = (ORIGIN, ORIGIN + point![300.0, 0.0]);
+    let cubic: Cubic = [start, start + (control - start) * (2.0 / 3.0), end + (control - end) * (2.0 / 3.0), end];
+    let quad = cubic.approx_quadratic(1e-6).unwrap();
+    assert!((quad[1] - control).norm() < 1e-6, "{:?}", quad[1]);
+}
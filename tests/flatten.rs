@@ -0,0 +1,100 @@
+use cucoqu::co2qu::{Conic, EvalAt, Flatten};
+use cucoqu::point;
+use cucoqu::{Cubic, CubicSpline, Point, Quad, Scalar};
+
+use cucoqu::scalar_consts::FRAC_1_SQRT_2;
+
+mod common;
+use common::{random_points, Rng};
+
+fn distance_to_polyline(p: Point, polyline: &[Point]) -> Scalar {
+    polyline
+        .windows(2)
+        .map(|w| {
+            let line = w[1] - w[0];
+            let u = if line.norm_squared() == 0.0 {
+                0.0
+            } else {
+                ((p - w[0]).dot(&line) / line.norm_squared()).clamp(0.0, 1.0)
+            };
+            (p - (w[0] + line * u)).norm()
+        })
+        .fold(Scalar::INFINITY, Scalar::min)
+}
+
+/// Every point of the curve is within `tolerance` of the polyline, and the polyline runs from the
+/// start to the end of the curve.
+fn assert_flat<S: EvalAt + Flatten>(segment: &S, tolerance: Scalar) -> usize {
+    assert_flat_sampled(segment, tolerance, 1000)
+}
+
+fn assert_flat_sampled<S: EvalAt + Flatten>(segment: &S, tolerance: Scalar, samples: usize) -> usize {
+    let polyline = segment.flatten(tolerance);
+    assert_eq!(polyline[0], segment.eval_at(0.0));
+    assert!((*polyline.last().unwrap() - segment.eval_at(1.0)).norm() < 1e-3);
+    for i in 0..=samples {
+        let p = segment.eval_at(i as Scalar / samples as Scalar);
+        let d = distance_to_polyline(p, &polyline);
+        assert!(d <= tolerance, "{} > {} at t={}", d, tolerance, i as Scalar / samples as Scalar);
+    }
+    polyline.len() - 1
+}
+
+#[test]
+fn flatten_quads() {
+    let quad: Quad = [point![0.0, 0.0], point![80.0, 10.0], point![20.0, 90.0]];
+    let coarse = assert_flat(&quad, 1.0);
+    let fine = assert_flat(&quad, 0.01);
+    // Lines needed grow with the inverse square root of the tolerance.
+    assert!(fine > coarse * 5 && fine < coarse * 15, "{} vs {}", fine, coarse);
+
+    let line: Quad = [point![0.0, 0.0], point![10.0, 10.0], point![20.0, 20.0]];
+    assert_eq!(line.flatten(0.1), vec![point![0.0, 0.0], point![20.0, 20.0]]);
+    // Turns back on itself at x = 20/3.
+    let backtrack: Quad = [point![0.0, 0.0], point![10.0, 0.0], point![5.0, 0.0]];
+    assert_eq!(backtrack.flatten(0.1).len(), 3);
+    assert_flat(&backtrack, 0.1);
+}
+
+#[test]
+fn flatten_cubics_and_conics() {
+    let s_curve: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]];
+    let cusp: Cubic = [point![0.0, 0.0], point![150.0, 100.0], point![50.0, 100.0], point![100.0, 0.0]];
+    for tolerance in [1.0, 0.1, 0.01] {
+        assert_flat(&s_curve, tolerance);
+        assert_flat(&cusp, tolerance);
+        for weight in [0.2, FRAC_1_SQRT_2, 1.0, 3.0] {
+            assert_flat(&Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], weight), tolerance);
+        }
+    }
+}
+
+#[test]
+fn flatten_spline() {
+    let spline: CubicSpline = vec![
+        [point![0.0, 0.0], point![10.0, 0.0], point![20.0, 0.0], point![30.0, 0.0]],
+        [point![30.0, 0.0], point![30.0, 10.0], point![30.0, 20.0], point![30.0, 40.0]],
+        [point![40.0, 40.0], point![50.0, 40.0], point![60.0, 40.0], point![70.0, 40.0]],
+    ];
+    // The joint is included once, and both ends of the gap. Straight cubics may still be split
+    // where cu2qu needed several quads, but only along the lines.
+    let polyline = spline.flatten(0.1);
+    for p in [point![0.0, 0.0], point![30.0, 0.0], point![30.0, 40.0], point![40.0, 40.0], point![70.0, 40.0]] {
+        assert_eq!(polyline.iter().filter(|&&q| q == p).count(), 1);
+    }
+    assert!(polyline.iter().all(|p| p[1] == 0.0 || p[0] == 30.0 || p[1] == 40.0));
+    assert!(polyline.windows(2).all(|w| w[0][0] <= w[1][0] && w[0][1] <= w[1][1]));
+}
+
+#[test]
+fn flatten_random_curves() {
+    for (i, p) in random_points(&mut Rng::new(0x9e37_79b9), 4 * 30).chunks(4).enumerate() {
+        let tolerance = [1.0, 0.25, 0.05][i % 3];
+        let quad: Quad = [p[0], p[1], p[2]];
+        assert_flat_sampled(&quad, tolerance, 200);
+        let cubic: Cubic = [p[0], p[1], p[2], p[3]];
+        assert_flat_sampled(&cubic, tolerance, 200);
+        let conic = Conic::new(p[0], p[1], p[2], [0.3, FRAC_1_SQRT_2, 4.0][i % 3]);
+        assert_flat_sampled(&conic, tolerance, 200);
+    }
+}
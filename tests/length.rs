@@ -0,0 +1,96 @@
+use cucoqu::co2qu::{ArcLength, Chop, Conic};
+use cucoqu::point;
+use cucoqu::{Cubic, CubicSpline, Point, Quad, Scalar};
+
+use cucoqu::scalar_consts::{FRAC_1_SQRT_2, PI};
+
+mod common;
+use common::assert_close;
+
+#[test]
+fn quad_closed_form() {
+    // A quad is also a (degree-elevated) cubic, whose length is integrated numerically.
+    let quad: Quad = [point![0.0, 0.0], point![80.0, 10.0], point![20.0, 90.0]];
+    let [p0, p1, p2] = quad;
+    let cubic: Cubic = [p0, p0 + (p1 - p0) * (2.0 / 3.0), p2 + (p1 - p2) * (2.0 / 3.0), p2];
+    assert_close(quad.arc_length(0.0), cubic.arc_length(1e-4), 1e-3);
+    assert_close(quad.length_at(0.3, 0.0), cubic.length_at(0.3, 1e-4), 1e-3);
+
+    let line: Quad = [point![0.0, 0.0], point![15.0, 20.0], point![30.0, 40.0]];
+    assert_close(line.arc_length(0.0), 50.0, 1e-4);
+    // Turns back on itself at x = 20/3.
+    let backtrack: Quad = [point![0.0, 0.0], point![10.0, 0.0], point![5.0, 0.0]];
+    assert_close(backtrack.arc_length(0.0), 20.0 / 3.0 + 5.0 / 3.0, 1e-4);
+    assert_close(backtrack.t_at_length(20.0 / 3.0, 1e-5), 2.0 / 3.0, 1e-3);
+}
+
+#[test]
+fn circle_lengths() {
+    let arc = Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], FRAC_1_SQRT_2);
+    assert_close(arc.arc_length(1e-4), 25.0 * PI, 1e-3);
+    // The cubic approximation of a circle is very slightly longer.
+    let k = 50.0 * 0.551_915;
+    let cubic: Cubic = [point![50.0, 0.0], point![50.0, k], point![k, 50.0], point![0.0, 50.0]];
+    assert_close(cubic.arc_length(1e-4), 25.0 * PI, 0.02);
+
+    // Lengths match those of the chopped off pieces.
+    for t in [0.1, 0.5, 0.75] {
+        let piece = arc.chop_at_t2(0.0, t);
+        assert_close(arc.length_at(t, 1e-4), piece.arc_length(1e-4), 1e-3);
+        let piece = cubic.chop_at_t2(0.0, t);
+        assert_close(cubic.length_at(t, 1e-4), piece.arc_length(1e-4), 1e-3);
+    }
+}
+
+#[test]
+fn length_round_trips() {
+    let s_curve: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]];
+    let cusp: Cubic = [point![0.0, 0.0], point![150.0, 100.0], point![50.0, 100.0], point![100.0, 0.0]];
+    for cubic in [s_curve, cusp] {
+        let total = cubic.arc_length(1e-4);
+        for i in 0..=10 {
+            let length = total * i as Scalar / 10.0;
+            let t = cubic.t_at_length(length, 1e-4);
+            assert_close(cubic.length_at(t, 1e-4), length, 1e-3);
+        }
+        assert_eq!(cubic.t_at_length(-1.0, 1e-4), 0.0);
+        assert_eq!(cubic.t_at_length(total + 1.0, 1e-4), 1.0);
+    }
+}
+
+#[test]
+fn spline_lengths() {
+    let spline: CubicSpline = vec![
+        [point![0.0, 0.0], point![10.0, 0.0], point![20.0, 0.0], point![30.0, 0.0]],
+        [point![30.0, 0.0], point![30.0, 10.0], point![30.0, 20.0], point![30.0, 40.0]],
+    ];
+    assert_close(spline.arc_length(1e-4), 70.0, 1e-3);
+    assert_close(spline.length_at(1.0, 1e-4), 30.0, 1e-3);
+    assert_close(spline.length_at(2.0, 1e-4), 70.0, 1e-3);
+    let t = spline.t_at_length(50.0, 1e-4);
+    assert!((1.0..2.0).contains(&t));
+    assert_close(spline.length_at(t, 1e-4), 50.0, 1e-3);
+    assert_close(spline.t_at_length(30.0, 1e-4), 1.0, 1e-3);
+    assert_eq!(spline.t_at_length(100.0, 1e-4), 2.0);
+}
+
+#[test]
+fn lengths_beyond_the_ends() {
+    let cubic: Cubic = [point![0.0, 0.0], point![10.0, 0.0], point![20.0, 0.0], point![30.0, 0.0]];
+    let quad: Quad = [point![0.0, 0.0], point![15.0, 0.0], point![30.0, 0.0]];
+    let arc = Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], FRAC_1_SQRT_2);
+    assert_eq!(cubic.length_at(-0.5, 1e-4), 0.0);
+    assert_close(cubic.length_at(1.5, 1e-4), 30.0, 1e-3);
+    assert_eq!(quad.length_at(-0.5, 0.0), 0.0);
+    assert_close(quad.length_at(1.5, 0.0), 30.0, 1e-3);
+    assert_eq!(arc.length_at(-0.5, 1e-4), 0.0);
+    assert_close(arc.length_at(1.5, 1e-4), 25.0 * PI, 1e-3);
+
+    let spline: CubicSpline = vec![
+        [point![0.0, 0.0], point![10.0, 0.0], point![20.0, 0.0], point![30.0, 0.0]],
+        [point![30.0, 0.0], point![30.0, 10.0], point![30.0, 20.0], point![30.0, 40.0]],
+    ];
+    assert_eq!(spline.length_at(-1.0, 1e-4), 0.0);
+    assert_close(spline.length_at(2.5, 1e-4), 70.0, 1e-3);
+    assert_close(spline.length_at(7.0, 1e-4), 70.0, 1e-3);
+}
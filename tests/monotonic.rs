@@ -0,0 +1,98 @@
+use cucoqu::co2qu::{Chop, ChopAtExtrema, Conic, EvalAt, Extrema};
+use cucoqu::point;
+use cucoqu::{Cubic, Point, Quad, Scalar};
+
+/// A segment whose control polygon is monotonic in an axis is monotonic in that axis.
+fn assert_monotonic(hull: &[Point], axis: usize) {
+    let increasing = hull.windows(2).all(|w| w[0][axis] <= w[1][axis]);
+    let decreasing = hull.windows(2).all(|w| w[0][axis] >= w[1][axis]);
+    assert!(increasing || decreasing, "{:?} not monotonic in axis {}", hull, axis);
+}
+
+fn assert_joined(hulls: &[Vec<Point>], start: Point, end: Point) {
+    assert_eq!(hulls[0][0], start);
+    assert_eq!(*hulls.last().unwrap().last().unwrap(), end);
+    for w in hulls.windows(2) {
+        assert_eq!(w[0].last(), w[1].first());
+    }
+}
+
+#[test]
+fn cubic_chop_at_t2() {
+    let cubic: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![70.0, -100.0], point![100.0, 0.0]];
+    let sub = cubic.chop_at_t2(0.2, 0.7);
+    for i in 0..=10 {
+        let s = i as Scalar / 10.0;
+        let expected = cubic.eval_at(0.2 + 0.5 * s);
+        assert!((sub.eval_at(s) - expected).norm() < 1e-3);
+    }
+    let [first, second] = cubic.chop();
+    assert_eq!(first[0], cubic[0]);
+    assert_eq!(second[3], cubic[3]);
+    assert_eq!(first[3], second[0]);
+    // Pieces just inside the ends aren't taken for the whole of them.
+    let t = 1e-6;
+    let (head, tail) = (cubic.chop_at_t2(t, 0.5), cubic.chop_at_t2(0.5, 1.0 - t));
+    assert!(head[0] != cubic[0] && (head[0] - cubic.eval_at(t)).norm() < 1e-5, "{:?}", head[0]);
+    assert!(tail[3] != cubic[3] && (tail[3] - cubic.eval_at(1.0 - t)).norm() < 1e-5, "{:?}", tail[3]);
+}
+
+#[test]
+fn cubic_monotonic() {
+    let s_curve: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![70.0, -100.0], point![100.0, 0.0]];
+    let ys = s_curve.y_extrema();
+    let pieces = s_curve.chop_at_y_extrema();
+    assert_eq!(pieces.len(), 3);
+    for (piece, t) in pieces.iter().zip(ys) {
+        assert!((piece[3] - s_curve.eval_at(t)).norm() < 1e-3);
+        assert_eq!(piece[2][1], piece[3][1]);
+    }
+    let hulls: Vec<Vec<Point>> = pieces.iter().map(|p| p.to_vec()).collect();
+    assert_joined(&hulls, s_curve[0], s_curve[3]);
+    hulls.iter().for_each(|h| assert_monotonic(h, 1));
+
+    let loopy: Cubic = [point![0.0, 0.0], point![-50.0, 80.0], point![150.0, 50.0], point![100.0, 0.0]];
+    let pieces = loopy.make_monotonic();
+    assert_eq!(pieces.len(), 1 + loopy.x_extrema().len() + loopy.y_extrema().len());
+    let hulls: Vec<Vec<Point>> = pieces.iter().map(|p| p.to_vec()).collect();
+    assert_joined(&hulls, loopy[0], loopy[3]);
+    for h in &hulls {
+        assert_monotonic(h, 0);
+        assert_monotonic(h, 1);
+    }
+}
+
+#[test]
+fn quad_monotonic() {
+    let quad: Quad = [point![0.0, 0.0], point![120.0, 100.0], point![10.0, 20.0]];
+    let pieces = quad.make_monotonic();
+    assert_eq!(pieces.len(), 3);
+    let hulls: Vec<Vec<Point>> = pieces.iter().map(|p| p.to_vec()).collect();
+    assert_joined(&hulls, quad[0], quad[2]);
+    for h in &hulls {
+        assert_monotonic(h, 0);
+        assert_monotonic(h, 1);
+    }
+    let monotonic: Quad = [point![0.0, 0.0], point![50.0, 10.0], point![100.0, 100.0]];
+    assert_eq!(monotonic.make_monotonic(), vec![monotonic]);
+}
+
+#[test]
+fn conic_monotonic() {
+    for weight in [0.5, 1.0, 2.0] {
+        let conic = Conic::new(point![0.0, 0.0], point![120.0, 100.0], point![10.0, 20.0], weight);
+        let pieces = conic.make_monotonic();
+        assert_eq!(pieces.len(), 3);
+        let hulls: Vec<Vec<Point>> = pieces.iter().map(|c| vec![c.start, c.control, c.end]).collect();
+        assert_joined(&hulls, conic.start, conic.end);
+        for h in &hulls {
+            assert_monotonic(h, 0);
+            assert_monotonic(h, 1);
+        }
+        let y = conic.chop_at_y_extrema();
+        assert_eq!(y.len(), 2);
+        assert!((y[0].end - conic.eval_at(conic.y_extrema()[0])).norm() < 1e-3);
+        assert_eq!(y[0].control[1], y[0].end[1]);
+        assert_eq!(y[1].control[1], y[1].start[1]);
+    }
+}
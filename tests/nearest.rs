@@ -0,0 +1,97 @@
+use cucoqu::co2qu::{Conic, EvalAt, Nearest};
+use cucoqu::point;
+use cucoqu::{Cubic, CubicSpline, Point, Quad, Scalar};
+
+use cucoqu::scalar_consts::FRAC_1_SQRT_2;
+
+mod common;
+use common::{random_cubic, Rng};
+
+/// The nearest point must be at least as near as any of many samples.
+fn assert_nearest<S: EvalAt + Nearest>(segment: &S, p: Point) {
+    let projection = segment.nearest(p);
+    assert!((segment.eval_at(projection.t) - projection.point).norm() < 1e-4);
+    assert!(((projection.point - p).norm() - projection.distance).abs() < 1e-4);
+    let brute = (0..=1000)
+        .map(|i| (segment.eval_at(i as Scalar / 1000.0) - p).norm())
+        .fold(Scalar::INFINITY, Scalar::min);
+    assert!(projection.distance <= brute + 1e-3, "{} > {} for {:?}", projection.distance, brute, p);
+}
+
+fn grid() -> impl Iterator<Item = Point> {
+    (-2..=7).flat_map(|x| (-4..=4).map(move |y| point![x as Scalar * 20.0, y as Scalar * 20.0]))
+}
+
+#[test]
+fn nearest_on_quad() {
+    // y = x² with its vertex at t=¼.
+    let quad: Quad = [point![-1.0, 1.0], point![1.0, -3.0], point![3.0, 9.0]];
+    let projection = quad.nearest(point![0.0, -1.0]);
+    assert!((projection.t - 0.25).abs() < 1e-5);
+    assert!(projection.point.norm() < 1e-5);
+    assert!((projection.distance - 1.0).abs() < 1e-5);
+
+    let quad: Quad = [point![0.0, 0.0], point![80.0, 10.0], point![20.0, 90.0]];
+    grid().for_each(|p| assert_nearest(&quad, p));
+}
+
+#[test]
+fn nearest_on_cubic() {
+    let s_curve: Cubic = [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]];
+    let cusp: Cubic = [point![0.0, 0.0], point![150.0, 100.0], point![50.0, 100.0], point![100.0, 0.0]];
+    for cubic in [s_curve, cusp] {
+        grid().for_each(|p| assert_nearest(&cubic, p));
+        // Points on the curve project onto themselves.
+        for t in [0.0, 0.3, 0.7, 1.0] {
+            let projection = cubic.nearest(cubic.eval_at(t));
+            assert!(projection.distance < 1e-3);
+        }
+    }
+}
+
+#[test]
+fn nearest_on_cubic_between_samples() {
+    // The nearest point lies on a short loop between the points at which any sampling of the
+    // curve would start looking for it.
+    let cubic: Cubic = [point![632.68, 455.76], point![861.26, 655.53], point![78.09, 95.43], point![824.66, 759.26]];
+    let projection = cubic.nearest(point![670.906, 491.7977]);
+    assert!(projection.distance < 0.01, "{:?}", projection);
+}
+
+#[test]
+fn nearest_on_random_curves() {
+    let mut rng = Rng::default();
+    for _ in 0..100 {
+        let cubic = random_cubic(&mut rng);
+        // Points near the curve, where a wrong local minimum is furthest from the nearest point.
+        let p = cubic.eval_at(rng.scalar() / 1000.0) + rng.point() / 100.0;
+        assert_nearest(&cubic, p);
+        let conic = Conic::new(cubic[0], cubic[1], cubic[2], rng.scalar() / 100.0);
+        let p = conic.eval_at(rng.scalar() / 1000.0) + rng.point() / 100.0;
+        assert_nearest(&conic, p);
+    }
+}
+
+#[test]
+fn nearest_on_conic() {
+    let arc = Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], FRAC_1_SQRT_2);
+    let projection = arc.nearest(point![10.0, 10.0]);
+    assert!((projection.point - point![50.0 * FRAC_1_SQRT_2, 50.0 * FRAC_1_SQRT_2]).norm() < 1e-3);
+    assert!((projection.distance - (50.0 - Scalar::sqrt(200.0))).abs() < 1e-3);
+    for weight in [0.2, 1.0, 3.0] {
+        let conic = Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], weight);
+        grid().for_each(|p| assert_nearest(&conic, p));
+    }
+}
+
+#[test]
+fn nearest_on_spline() {
+    let spline: CubicSpline = vec![
+        [point![0.0, 0.0], point![10.0, 0.0], point![20.0, 0.0], point![30.0, 0.0]],
+        [point![30.0, 0.0], point![30.0, 10.0], point![30.0, 20.0], point![30.0, 30.0]],
+    ];
+    let projection = spline.nearest(point![25.0, 20.0]);
+    assert!((projection.t - (1.0 + 2.0 / 3.0)).abs() < 1e-4);
+    assert!((projection.point - point![30.0, 20.0]).norm() < 1e-4);
+    assert!((projection.distance - 5.0).abs() < 1e-4);
+}
@@ -0,0 +1,86 @@
+use cucoqu::co2qu::{Conic, Resample};
+use cucoqu::point;
+use cucoqu::{CubicSpline, Point, QuadSpline, Scalar};
+
+use cucoqu::scalar_consts::FRAC_1_SQRT_2;
+
+fn assert_near(a: Point, b: Point) {
+    assert!((a - b).norm() < 1e-3, "{:?} != {:?}", a, b);
+}
+
+fn corner() -> CubicSpline {
+    vec![
+        [point![0.0, 0.0], point![10.0, 0.0], point![20.0, 0.0], point![30.0, 0.0]],
+        [point![30.0, 0.0], point![30.0, 10.0], point![30.0, 20.0], point![30.0, 40.0]],
+    ]
+}
+
+#[test]
+fn resample_across_segments() {
+    let samples = corner().resample(8, 1e-4);
+    assert_eq!(samples.len(), 8);
+    for (i, sample) in samples.iter().enumerate() {
+        let d = i as Scalar * 10.0;
+        let (expected, tangent) = if d < 30.0 {
+            (point![d, 0.0], point![1.0, 0.0])
+        } else {
+            (point![30.0, d - 30.0], point![0.0, 1.0])
+        };
+        assert_near(sample.point, expected);
+        // At the corner, either tangent will do.
+        if i != 3 {
+            assert_near(sample.tangent, tangent);
+        }
+    }
+    assert_eq!(samples[0].t, 0.0);
+    assert_eq!(samples[7].t, 2.0);
+    assert_eq!(samples[7].point, point![30.0, 40.0]);
+    assert!(samples.windows(2).all(|w| w[0].t < w[1].t));
+
+    assert_eq!(corner().resample(1, 1e-4).len(), 1);
+    assert!(corner().resample(0, 1e-4).is_empty());
+    let empty: QuadSpline = vec![];
+    assert!(empty.resample(5, 1e-4).is_empty());
+}
+
+#[test]
+fn resample_every_distance() {
+    let samples = corner().resample_every(15.0, 1e-4);
+    assert_eq!(samples.len(), 5);
+    assert_near(samples[2].point, point![30.0, 0.0]);
+    assert_near(samples[3].point, point![30.0, 15.0]);
+    assert_near(samples[4].point, point![30.0, 30.0]);
+    // The end is included when the length is a multiple of the distance.
+    assert_eq!(corner().resample_every(10.0, 1e-4).len(), 8);
+}
+
+#[test]
+fn resample_circle() {
+    let corners = [point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], point![-50.0, 50.0], point![-50.0, 0.0]];
+    let corners = [
+        corners.as_slice(),
+        &[point![-50.0, -50.0], point![0.0, -50.0], point![50.0, -50.0], point![50.0, 0.0]],
+    ]
+    .concat();
+    let circle: Vec<Conic> = corners.windows(3).step_by(2).map(|w| Conic::new(w[0], w[1], w[2], FRAC_1_SQRT_2)).collect();
+    let samples = circle.resample(13, 1e-4);
+    // Evenly spaced around the circle, every 30°.
+    for (i, sample) in samples.iter().enumerate() {
+        let angle = (i as Scalar * 30.0).to_radians();
+        assert_near(sample.point, point![50.0 * angle.cos(), 50.0 * angle.sin()]);
+        assert_near(sample.tangent, point![-angle.sin(), angle.cos()]);
+    }
+}
+
+#[test]
+fn resample_without_tangents() {
+    // A segment whose points coincide, at the end of the spline, has no tangent of its own.
+    let point = point![30.0, 0.0];
+    let spline: CubicSpline = vec![corner()[0], [point; 4]];
+    let samples = spline.resample(2, 1e-4);
+    assert_eq!(samples[1].t, 1.0);
+    assert_near(samples[1].tangent, point![1.0, 0.0]);
+    // Nor has a spline which is a single point.
+    let samples = [[point; 3]].resample(2, 1e-4);
+    assert!(samples.iter().all(|sample| sample.tangent == point![0.0, 0.0]));
+}
@@ -0,0 +1,126 @@
+use cucoqu::co2qu::{Bounds, Chop, Conic, ConicBatch, CubicBatch, EvalAt, QuadBatch, SegmentBatch};
+use cucoqu::{Cubic, Point, Quad, Rect, Scalar};
+
+mod common;
+use common::{random_points, Rng};
+
+// More than one chunk of lanes, and not a whole number of them.
+const COUNT: usize = 29;
+
+fn points(count: usize) -> Vec<Point> {
+    random_points(&mut Rng::default(), count)
+}
+
+fn cubics() -> Vec<Cubic> {
+    points(4 * COUNT).chunks(4).map(|p| [p[0], p[1], p[2], p[3]]).collect()
+}
+
+fn quads() -> Vec<Quad> {
+    points(3 * COUNT).chunks(3).map(|p| [p[0], p[1], p[2]]).collect()
+}
+
+fn conics() -> Vec<Conic> {
+    let weights = [0.1, 0.5, cucoqu::scalar_consts::FRAC_1_SQRT_2, 1.0, 2.0, 10.0];
+    quads().iter().zip(weights.iter().cycle()).map(|(q, &w)| Conic::new(q[0], q[1], q[2], w)).collect()
+}
+
+fn assert_near(a: Point, b: Point) {
+    assert!((a - b).norm() <= 1e-3 * (1.0 + b.norm()), "{:?} != {:?}", a, b);
+}
+
+fn assert_rect_near(a: &Rect, b: &Rect) {
+    assert_near(a.min, b.min);
+    assert_near(a.max, b.max);
+}
+
+#[test]
+fn round_trip() {
+    let cubics = cubics();
+    let batch: CubicBatch = cubics.iter().copied().collect();
+    assert_eq!(batch.len(), COUNT);
+    assert_eq!((0..COUNT).map(|i| batch.get(i)).collect::<Vec<_>>(), cubics);
+    assert!(CubicBatch::default().is_empty());
+
+    let conics = conics();
+    let batch: ConicBatch = conics.iter().cloned().collect();
+    let conic = batch.get(4);
+    assert_eq!(
+        (conic.start, conic.control, conic.end, conic.weight),
+        (conics[4].start, conics[4].control, conics[4].end, 2.0)
+    );
+}
+
+#[test]
+fn eval_like_segments() {
+    for t in [0.0, 0.25, 0.5, 0.9, 1.0] {
+        let cubics = cubics();
+        let points = cubics.iter().copied().collect::<CubicBatch>().eval_at(t);
+        assert_eq!(points.len(), COUNT);
+        cubics.iter().zip(points).for_each(|(c, p)| assert_near(p, c.eval_at(t)));
+
+        let quads = quads();
+        let points = quads.iter().copied().collect::<QuadBatch>().eval_at(t);
+        quads.iter().zip(points).for_each(|(q, p)| assert_near(p, q.eval_at(t)));
+
+        let conics = conics();
+        let points = conics.iter().cloned().collect::<ConicBatch>().eval_at(t);
+        conics.iter().zip(points).for_each(|(c, p)| assert_near(p, c.eval_at(t)));
+    }
+}
+
+#[test]
+fn eval_into_buffers() {
+    let batch: QuadBatch = quads().into_iter().collect();
+    let (mut xs, mut ys) = ([Scalar::NAN; COUNT + 3], [Scalar::NAN; COUNT + 3]);
+    batch.eval_at_into(0.5, &mut xs, &mut ys);
+    assert!(xs[..COUNT].iter().chain(&ys[..COUNT]).all(|c| c.is_finite()));
+    assert!(xs[COUNT..].iter().chain(&ys[COUNT..]).all(|c| c.is_nan()));
+}
+
+#[test]
+fn chop_like_segments() {
+    let t = 0.3;
+    let cubics = cubics();
+    let [first, second] = cubics.iter().copied().collect::<CubicBatch>().chop_at(t);
+    for (i, cubic) in cubics.iter().enumerate() {
+        let mut dst = [[Point::default(); 4]; 2];
+        assert!(cubic.chop_at(t, &mut dst));
+        assert_eq!([first.get(i), second.get(i)], dst);
+    }
+
+    let conics = conics();
+    let [first, second] = conics.iter().cloned().collect::<ConicBatch>().chop_at(t);
+    for (i, conic) in conics.iter().enumerate() {
+        let mut dst = [Conic::default(), Conic::default()];
+        assert!(conic.chop_at(t, &mut dst));
+        for (batch, expected) in [first.get(i), second.get(i)].iter().zip(&dst) {
+            assert_near(batch.start, expected.start);
+            assert_near(batch.control, expected.control);
+            assert_near(batch.end, expected.end);
+            assert!((batch.weight - expected.weight).abs() <= 1e-4 * expected.weight, "{}", batch.weight);
+        }
+    }
+}
+
+#[test]
+fn bounds_like_segments() {
+    let cubics = cubics();
+    let batch: CubicBatch = cubics.iter().copied().collect();
+    for (rect, cubic) in batch.tight_bounds().iter().zip(&cubics) {
+        assert_rect_near(rect, &cubic.tight_bounds());
+    }
+    assert_eq!(batch.fast_bounds(), cubics.iter().map(Bounds::fast_bounds).collect::<Vec<_>>());
+
+    let quads = quads();
+    let batch: QuadBatch = quads.iter().copied().collect();
+    for (rect, quad) in batch.tight_bounds().iter().zip(&quads) {
+        assert_rect_near(rect, &quad.tight_bounds());
+    }
+
+    let conics = conics();
+    let batch: ConicBatch = conics.iter().cloned().collect();
+    assert_eq!(batch.tight_bounds().len(), COUNT);
+    for (rect, conic) in batch.tight_bounds().iter().zip(&conics) {
+        assert_rect_near(rect, &conic.tight_bounds());
+    }
+}
@@ -0,0 +1,92 @@
+use cucoqu::co2qu::{max_distance, Bounds, Conic, ToQuads, Tolerance};
+use cucoqu::cu2qu::CurveToQuadratic;
+use cucoqu::point;
+use cucoqu::{Cubic, Point, Scalar};
+
+use cucoqu::scalar_consts::FRAC_1_SQRT_2;
+
+fn scaled(cubic: &Cubic, scale: Scalar) -> Cubic {
+    cubic.map(|p| p * scale)
+}
+
+fn s_curve() -> Cubic {
+    [point![0.0, 0.0], point![30.0, 100.0], point![60.0, -100.0], point![100.0, 0.0]]
+}
+
+#[test]
+fn absolute_for_each_kind() {
+    let cubic = s_curve();
+    assert_eq!(Tolerance::Absolute(0.5).absolute_for(&cubic), 0.5);
+    assert_eq!(Tolerance::from(0.5), Tolerance::Absolute(0.5));
+    let upm = Tolerance::Upm {
+        fraction: 0.001,
+        units_per_em: 2048.0,
+    };
+    assert_eq!(upm.absolute_for(&cubic), 2.048);
+    assert_eq!(Tolerance::Relative(0.001).absolute_for(&cubic), cubic.tight_bounds().diagonal() * 0.001);
+}
+
+#[test]
+fn absolute_is_as_before() {
+    let cubic = s_curve();
+    assert_eq!(cubic.to_quads(Tolerance::Absolute(0.5)).unwrap(), cubic.curve_to_quadratic(0.5).unwrap());
+    assert_eq!(cubic.to_quads(0.5.into()).unwrap(), cubic.curve_to_quadratic(0.5).unwrap());
+}
+
+#[test]
+fn upm_scales_with_the_font() {
+    // The same glyph outline at 1000 and 4096 UPM needs the same number of quads.
+    let counts: Vec<usize> = [1000.0, 4096.0]
+        .into_iter()
+        .map(|units_per_em| {
+            let tolerance = Tolerance::Upm {
+                fraction: 0.001,
+                units_per_em,
+            };
+            let cubic = scaled(&s_curve(), units_per_em / 100.0);
+            let quads = cubic.to_quads(tolerance).unwrap();
+            assert!(max_distance(&cubic, &quads).distance <= tolerance.absolute_for(&cubic));
+            quads.len()
+        })
+        .collect();
+    assert_eq!(counts[0], counts[1]);
+}
+
+#[test]
+fn relative_scales_with_the_curve() {
+    let tolerance = Tolerance::Relative(0.001);
+    let (tiny, huge) = (scaled(&s_curve(), 0.1), scaled(&s_curve(), 100.0));
+    let (tiny_quads, huge_quads) = (tiny.to_quads(tolerance).unwrap(), huge.to_quads(tolerance).unwrap());
+    assert_eq!(tiny_quads.len(), huge_quads.len());
+    assert!(max_distance(&huge, &huge_quads).distance <= tolerance.absolute_for(&huge));
+    assert_eq!(tolerance.absolute_for(&huge), huge.tight_bounds().diagonal() * 0.001);
+    // Whereas an absolute tolerance approximates the tiny curve with a single quad.
+    assert!(tiny.to_quads(Tolerance::Absolute(1.0)).unwrap().len() < huge.to_quads(Tolerance::Absolute(1.0)).unwrap().len());
+}
+
+#[test]
+fn conics_and_splines() {
+    let arc = [
+        Conic::new(point![50.0, 0.0], point![50.0, 50.0], point![0.0, 50.0], FRAC_1_SQRT_2),
+        Conic::new(point![0.0, 50.0], point![-50.0, 50.0], point![-50.0, 0.0], FRAC_1_SQRT_2),
+    ];
+    let tolerance = Tolerance::Relative(0.01);
+    let quads = arc[0].to_quads(tolerance).unwrap();
+    assert_eq!(quads, arc[0].as_quads(tolerance.absolute_for(&arc[0])));
+    assert!(max_distance(&arc[0], &quads).distance <= 50.0 * cucoqu::scalar_consts::SQRT_2 * 0.01);
+    let spline = arc.to_quads(tolerance).unwrap();
+    assert_eq!(spline.len(), quads.len() * 2);
+    assert_eq!(spline[quads.len() - 1][2], point![0.0, 50.0]);
+}
+
+#[test]
+fn relative_on_tiny_segments() {
+    // A point, and a curve too small for its coordinates to tell a thousandth of it apart.
+    let p = point![3000.0, 2000.0];
+    let tiny: Cubic = [p, p + point![0.001, 0.002], p + point![0.002, -0.001], p + point![0.003, 0.0]];
+    for cubic in [[p; 4], tiny] {
+        assert!(Tolerance::Relative(0.001).absolute_for(&cubic) > 0.0);
+        assert!(cubic.to_quads(Tolerance::Relative(0.001)).is_ok());
+    }
+    assert_eq!(Conic::new(p, p, p, FRAC_1_SQRT_2).to_quads(Tolerance::Relative(0.001)).unwrap().len(), 1);
+}
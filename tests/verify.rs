@@ -0,0 +1,99 @@
+use cucoqu::co2qu::{verify, Conic, Violation};
+use cucoqu::cu2qu::CurveToQuadratic;
+use cucoqu::point;
+use cucoqu::qu2cu::QuadToCubic;
+use cucoqu::{Cubic, Point, Quad, QuadSpline};
+
+use cucoqu::scalar_consts::FRAC_1_SQRT_2;
+
+mod common;
+use common::{random_cubic, Rng};
+
+fn cubic() -> Cubic {
+    [point![0.0, 0.0], point![0.0, 200.0], point![300.0, 400.0], point![500.0, 100.0]]
+}
+
+#[test]
+fn converters_pass() {
+    let cubic = cubic();
+    let quads = cubic.curve_to_quadratic(1.0).unwrap();
+    assert_eq!(verify(&cubic, &quads, 1.0), Ok(()));
+
+    let conic = Conic::new(point![100.0, 0.0], point![100.0, 100.0], point![0.0, 100.0], FRAC_1_SQRT_2);
+    let quads: QuadSpline = conic.as_quads(0.25);
+    assert_eq!(verify(&conic, &quads, 0.25), Ok(()));
+
+    let quad: Quad = [point![0.0, 0.0], point![80.0, 10.0], point![20.0, 90.0]];
+    assert_eq!(verify(&quad, &[quad.quad_to_cubic()], 1e-3), Ok(()));
+}
+
+#[test]
+fn empty_approximation() {
+    assert_eq!(verify::<Cubic, Quad>(&cubic(), &[], 1.0), Err(Violation::Empty));
+}
+
+#[test]
+fn moved_end() {
+    let cubic = cubic();
+    let mut quads = cubic.curve_to_quadratic(1.0).unwrap();
+    quads.last_mut().unwrap()[2] = point![500.0, 102.0];
+    let violation = verify(&cubic, &quads, 1.0).unwrap_err();
+    assert_eq!(violation, Violation::EndMoved { gap: 2.0 });
+    assert_eq!(violation.t(), 1.0);
+}
+
+#[test]
+fn kinked_joint() {
+    let cubic = cubic();
+    let mut quads = cubic.curve_to_quadratic(1.0).unwrap();
+    assert!(quads.len() > 2);
+    // Nudge the on-curve point between the first two quads off the line between their controls.
+    let joint = quads[0][2] + point![0.5, -0.5];
+    quads[0][2] = joint;
+    quads[1][0] = joint;
+    match verify(&cubic, &quads, 1.0) {
+        Err(violation @ Violation::Discontinuity { joint, t }) => {
+            assert_eq!(joint.index, 0);
+            assert!(t > 0.0 && t < 0.5, "{}", t);
+            assert_eq!(violation.t(), t);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn too_far() {
+    let cubic = cubic();
+    let quads = cubic.curve_to_quadratic(1.0).unwrap();
+    match verify(&cubic, &quads, 0.01) {
+        Err(Violation::TooFar(deviation)) => {
+            assert!(deviation.distance > 0.01 && deviation.distance <= 1.0, "{:?}", deviation);
+            assert!(deviation.t > 0.0 && deviation.t < 1.0, "{:?}", deviation);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn turned_end_tangent() {
+    // A line can be approximated within tolerance by a curve which leaves at the wrong angle.
+    let line: Quad = [point![0.0, 0.0], point![50.0, 0.0], point![100.0, 0.0]];
+    let bent: Quad = [point![0.0, 0.0], point![50.0, 1.0], point![100.0, 0.0]];
+    match verify(&line, &[bent], 1.0) {
+        Err(Violation::StartTangent { angle }) => assert!((angle - 0.02).abs() < 1e-3, "{}", angle),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn cu2qu_passes_on_random_cubics() {
+    let mut rng = Rng::default();
+    for _ in 0..40 {
+        let cubic = random_cubic(&mut rng);
+        let tolerance = rng.scalar() / 500.0 + 0.1;
+        if let Ok(quads) = cubic.curve_to_quadratic(tolerance) {
+            let result = verify(&cubic, &quads, tolerance);
+            assert!(result.is_ok(), "{:?} for {:?} within {}", result, cubic, tolerance);
+        }
+    }
+}